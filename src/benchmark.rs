@@ -0,0 +1,184 @@
+use crate::*;
+
+/// One setup to measure.
+#[derive(Clone, Debug)]
+pub struct BenchmarkCase {
+	pub container: FieldContainer,
+	pub topology: FieldTopology,
+	/// Width and height of the world
+	pub size: i32,
+	/// Initial count of bots divided by count of cells
+	pub density: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchmarkResult {
+	pub case: BenchmarkCase,
+	pub steps: usize,
+	/// Sum of counts of bots before each step
+	pub bots: usize,
+	pub seconds: f64,
+}
+
+impl BenchmarkResult {
+	pub fn steps_per_second(&self) -> f64 {
+		self.steps as f64 / self.seconds
+	}
+
+	pub fn bots_per_second(&self) -> f64 {
+		self.bots as f64 / self.seconds
+	}
+}
+
+impl BenchmarkCase {
+	/// Error if the size or the density can't be used with any container and topology
+	pub fn constants(&self) -> Result<Constants, String> {
+		if self.size <= 0 {
+			return Err(format!("Size should be positive, found {}", self.size));
+		}
+		if !(0.0..=1.0).contains(&self.density) {
+			return Err(format!("Density should be from 0 to 1, found {}", self.density));
+		}
+		let cells = u64::from(self.size as u32) * u64::from(self.size as u32);
+		// 5 resources per cell, their sum should fit into u32
+		if cells > u64::from(u32::MAX / 5) {
+			return Err(format!("Size {} is too big, resources of the world don't fit into 32 bits", self.size));
+		}
+		let cells = cells as u32;
+		Ok(Constants {
+			width: self.size,
+			height: self.size,
+			scale: 1.0,
+			image_scale: 1,
+			benchmark: true,
+
+			bots: (f64::from(cells) * f64::from(self.density)) as usize,
+			// Same amount per cell as in the default 100×100 world
+			protein: cells * 3,
+			oxygen: cells,
+			carbon: cells,
+
+			die: 320,
+			live: 160,
+			comand: 2,
+			multiply: 4,
+			seed: 92,
+
+			topology: self.topology.clone(),
+			container: self.container.clone(),
+			neighborhood: FieldNeighborhood::Moore,
+			sense: 1,
+			update: UpdateOrder::Sorted,
+			rules: RulesetVariant::Default,
+			genomes: None,
+		})
+	}
+}
+
+/// Every valid combination of container and topology for each size and density. Error if some size or density can't be used at all.
+pub fn benchmark_cases(sizes: &[i32], densities: &[f32]) -> Result<Vec<BenchmarkCase>, String> {
+	let mut result = Vec::new();
+	for size in sizes {
+		for density in densities {
+			for container in FieldContainer::iter() {
+				for topology in FieldTopology::iter() {
+					let case = BenchmarkCase { container: container.clone(), topology, size: *size, density: *density };
+					if case.constants()?.validate().is_ok() {
+						result.push(case);
+					}
+				}
+			}
+		}
+	}
+	Ok(result)
+}
+
+struct BenchmarkRun {
+	steps: usize,
+}
+
+impl GridVisitor for BenchmarkRun {
+	type Output = (usize, f64);
+
+	fn visit<G: 'static + Grid<Bot>>(self, constants: Constants, grid: G) -> Self::Output {
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut bots = 0;
+		let seconds = seconds_of(|| {
+			let mut world = init_world(&constants, &mut rng, grid);
+			for _ in 0..self.steps {
+				bots += world.bots.len();
+				process_world(constants.rules.ruleset(), &constants, &mut rng, &mut world, &mut ());
+			}
+		});
+		(bots, seconds)
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn seconds_of<F: FnOnce()>(f: F) -> f64 {
+	let start = std::time::Instant::now();
+	f();
+	start.elapsed().as_secs_f64()
+}
+
+// `Instant` is not implemented on the web
+#[cfg(all(target_arch = "wasm32", feature = "gui"))]
+fn seconds_of<F: FnOnce()>(f: F) -> f64 {
+	time(|_| f()).seconds
+}
+
+pub fn run_benchmark_case(case: &BenchmarkCase, steps: usize) -> Result<BenchmarkResult, String> {
+	let (bots, seconds) = visit_grid(case.constants()?, BenchmarkRun { steps })?;
+	Ok(BenchmarkResult {
+		case: case.clone(),
+		steps,
+		bots,
+		seconds,
+	})
+}
+
+pub fn benchmark_table(results: &[BenchmarkResult]) -> String {
+	let mut text = format!(
+		"{:<10}{:<20}{:>6}{:>9}{:>12}{:>10}{:>14}\n",
+		"container", "topology", "size", "density", "bots/step", "steps/s", "10³ bots/s"
+	);
+	for result in results {
+		text += &format!(
+			"{:<10}{:<20}{:>6}{:>9.3}{:>12.1}{:>10.1}{:>14.1}\n",
+			format!("{:?}", result.case.container),
+			format!("{:?}", result.case.topology),
+			result.case.size,
+			result.case.density,
+			result.bots as f64 / result.steps as f64,
+			result.steps_per_second(),
+			result.bots_per_second() / 1000.0,
+		);
+	}
+	text
+}
+
+pub fn benchmark_csv(results: &[BenchmarkResult]) -> String {
+	let mut text = "container,topology,size,density,steps,bots,seconds\n".to_string();
+	for result in results {
+		text += &format!(
+			"{:?},{:?},{},{},{},{},{}\n",
+			result.case.container,
+			result.case.topology,
+			result.case.size,
+			result.case.density,
+			result.steps,
+			result.bots,
+			result.seconds,
+		);
+	}
+	text
+}
+
+/// Small suite for the `benchmark` flag of the interactive window
+pub fn run_benchmark() -> Result<String, String> {
+	let results = benchmark_cases(&[64, 256], &[0.05, 0.25])?
+		.iter()
+		.map(|case| run_benchmark_case(case, 100))
+		.collect::<Result<Vec<BenchmarkResult>, String>>()?;
+	Ok(benchmark_table(&results))
+}
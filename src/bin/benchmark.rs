@@ -0,0 +1,54 @@
+use std::path::Path;
+use crabots::*;
+
+fn main() {
+	if let Err(message) = run() {
+		eprintln!("{}", message);
+		std::process::exit(1);
+	}
+}
+
+/// Each item should satisfy `valid`, otherwise `requirement` is shown
+fn parse_list<T: std::str::FromStr, F: Fn(&T) -> bool>(name: &str, value: &str, valid: F, requirement: &str) -> Result<Vec<T>, String> where T::Err: std::fmt::Debug {
+	value
+		.split(',')
+		.map(|item| {
+			let parsed = item.trim().parse().map_err(|e| format!("Can't parse {} `{}`: {:?}", name, item, e))?;
+			if valid(&parsed) {
+				Ok(parsed)
+			} else {
+				Err(format!("Wrong {} `{}`: {}", name, item, requirement))
+			}
+		})
+		.collect()
+}
+
+fn run() -> Result<(), String> {
+	let matches = clap_app!(benchmark =>
+		(version: env!("CARGO_PKG_VERSION"))
+		(about: "Measures speed of simulation for every container and topology")
+		(@arg steps: -n --steps +takes_value default_value("100") "Count of simulation steps in each run")
+		(@arg sizes: -s --sizes +takes_value default_value("64,256,1024") "Comma separated widths of square worlds")
+		(@arg densities: -d --densities +takes_value default_value("0.05,0.25") "Comma separated initial counts of bots per cell")
+		(@arg output: -o --output +takes_value "Also write results to this CSV file")
+	).get_matches();
+
+	let steps: usize = matches.value_of("steps").unwrap().parse().map_err(|e| format!("Can't parse steps: {:?}", e))?;
+	let sizes: Vec<i32> = parse_list("sizes", matches.value_of("sizes").unwrap(), |size| *size > 0, "should be positive")?;
+	let densities: Vec<f32> = parse_list("densities", matches.value_of("densities").unwrap(), |density| (0.0..=1.0).contains(density), "should be from 0 to 1")?;
+
+	let cases = benchmark_cases(&sizes, &densities)?;
+	let mut results = Vec::new();
+	for (i, case) in cases.iter().enumerate() {
+		eprintln!("{}/{}: {:?} {:?} {}×{}, density {}", i + 1, cases.len(), case.container, case.topology, case.size, case.size, case.density);
+		results.push(run_benchmark_case(case, steps)?);
+	}
+	print!("{}", benchmark_table(&results));
+
+	if let Some(output) = matches.value_of("output") {
+		let output = Path::new(output);
+		std::fs::write(output, benchmark_csv(&results)).map_err(|e| format!("Can't write {}: {}", output.display(), e))?;
+	}
+
+	Ok(())
+}
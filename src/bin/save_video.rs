@@ -1,10 +1,151 @@
-use std::io::ErrorKind::AlreadyExists;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use crabots::*;
 
+#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug)]
+enum Format {
+	/// Sequence of png images in the output directory
+	Png,
+	/// Uncompressed YUV 4:4:4 video, can be read by ffmpeg directly
+	Y4m,
+	/// Raw rgb24 frames one after another
+	Raw,
+}
+
+/// World downscaled by averaging square blocks of cells. Empty cells are black.
+struct Frame {
+	size: Vec2i,
+	block: i32,
+	sum: Vec<ColorSum>,
+	/// Count of cells in each block, blocks on the right and bottom edges can be smaller
+	cells: Vec<u32>,
+}
+
+impl Frame {
+	fn new(world_size: &Vec2i, block: i32) -> Self {
+		let size = Vec2i::new(
+			(world_size.x + block - 1) / block,
+			(world_size.y + block - 1) / block,
+		);
+		let len = (size.x * size.y) as usize;
+		let mut cells = vec![0; len];
+		for y in 0..world_size.y {
+			for x in 0..world_size.x {
+				cells[(x / block + y / block * size.x) as usize] += 1;
+			}
+		}
+		Frame {
+			size,
+			block,
+			sum: vec![ColorSum::default(); len],
+			cells,
+		}
+	}
+
+	fn clear(&mut self) {
+		for color in &mut self.sum {
+			*color = ColorSum::default();
+		}
+	}
+
+	fn add(&mut self, pos: &Vec2i, color: &Color) {
+		self.sum[(pos.x / self.block + pos.y / self.block * self.size.x) as usize].add(color);
+	}
+
+	fn pixels<'a>(&'a self) -> impl Iterator<Item = [u8; 3]> + 'a {
+		self.sum.iter().zip(self.cells.iter()).map(|(sum, cells)| {
+			let color = sum.average(*cells);
+			[color.r, color.g, color.b]
+		})
+	}
+
+	fn save_png(&self, path: &Path) -> Result<(), String> {
+		let mut image = Image::new(&self.size.clone().into());
+		for (index, [r, g, b]) in self.pixels().enumerate() {
+			let pos = Vec2i::new(index as i32 % self.size.x, index as i32 / self.size.x);
+			set_pixel(&mut image, &pos, &Color::rgba(r, g, b, 255));
+		}
+		image.save_png(path).map_err(|e| format!("Can't save image {}: {:?}", path.display(), e))
+	}
+
+	fn write_y4m<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+		writer.write_all(b"FRAME\n")?;
+		// BT.601, limited range. Planes are written one after another
+		let planes: [fn(i32, i32, i32) -> i32; 3] = [
+			|r, g, b| ((66 * r + 129 * g + 25 * b + 128) >> 8) + 16,
+			|r, g, b| ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128,
+			|r, g, b| ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128,
+		];
+		for plane in planes.iter() {
+			let bytes: Vec<u8> = self.pixels()
+				.map(|[r, g, b]| plane(i32::from(r), i32::from(g), i32::from(b)) as u8)
+				.collect();
+			writer.write_all(&bytes)?;
+		}
+		Ok(())
+	}
+
+	fn write_raw<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+		for pixel in self.pixels() {
+			writer.write_all(&pixel)?;
+		}
+		Ok(())
+	}
+}
+
 fn main() {
+	if let Err(message) = run() {
+		eprintln!("{}", message);
+		std::process::exit(1);
+	}
+}
+
+fn run() -> Result<(), String> {
+	let matches = clap_app!(save_video =>
+		(version: env!("CARGO_PKG_VERSION"))
+		(about: "Runs simulation without window and records it as images or video")
+		(@arg width: -w --width +takes_value default_value("1000") "Width of world grid")
+		(@arg height: -g --height +takes_value default_value("1000") "Height of world grid")
+		(@arg steps: -n --steps +takes_value default_value("10800") "Count of simulation steps")
+		(@arg skip: -k --skip +takes_value default_value("1") "Only every <skip> step is recorded")
+		(@arg block: -b --block +takes_value default_value("5") "Square of <block>×<block> cells is averaged into one pixel")
+		(@arg format: -f --format +takes_value default_value("Png") "Output format: Png, Y4m or Raw")
+		(@arg fps: --fps +takes_value default_value("30") "Frames per second written to Y4m header")
+		(@arg output: -o --output +takes_value default_value("images") "Output directory for Png, output file otherwise")
+		(@arg seed: -e --seed +takes_value default_value("92") "Seed to random generator")
+		(@arg rules: --rules +takes_value default_value("Default") "Rules of the step of bot: Default, Ageless or Peaceful")
+		(@arg genomes: --genomes +takes_value "Initial bots are made from genomes in this TOML file, the rest is random")
+		(@arg export_genomes: --("export-genomes") +takes_value "Write genomes of alive bots to this TOML file after the last step")
+	).get_matches();
+
+	macro_rules! arg_parse {
+		($name:literal) => {
+			matches
+				.value_of($name)
+				.ok_or(format!("No default value for {}", $name))?
+				.parse()
+				.map_err(|e| format!("Can't parse {}: {:?}", $name, e))?
+		};
+	}
+
+	let steps: usize = arg_parse!("steps");
+	let skip: usize = arg_parse!("skip");
+	let block: i32 = arg_parse!("block");
+	let format: Format = matches
+		.value_of("format")
+		.unwrap()
+		.parse()
+		.map_err(|_| format!("Format can only be: {:?}", Format::iter().collect::<Vec<_>>()))?;
+	let fps: u32 = arg_parse!("fps");
+	let output = Path::new(matches.value_of("output").unwrap());
+	if skip == 0 || block <= 0 {
+		return Err("Skip and block should be positive".to_string());
+	}
+
 	let constants = Constants {
-		width: 1000,
-		height: 1000,
+		width: arg_parse!("width"),
+		height: arg_parse!("height"),
 		scale: 1.0,
 		image_scale: 1,
 		benchmark: true,
@@ -18,51 +159,83 @@ fn main() {
 		live: 160,
 		comand: 2,
 		multiply: 4,
-		seed: 92,
+		seed: arg_parse!("seed"),
 
 		topology: FieldTopology::Torus,
 		container: FieldContainer::Vec,
+		neighborhood: FieldNeighborhood::Moore,
+		sense: 1,
+		update: UpdateOrder::Sorted,
+		rules: matches
+			.value_of("rules")
+			.unwrap()
+			.parse()
+			.map_err(|_| format!("Rules can only be: {:?}", RulesetVariant::iter().collect::<Vec<_>>()))?,
+		genomes: matches.value_of("genomes").map(|path| path.to_string()),
 	};
-	let steps = 10800;
+	constants.validate()?;
 	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
 	let grid = VecGrid::<Bot, TorusSpace>::new(&constants.size());
-	let mut bots = 0;
-	let mut image = Image::new(&Vec2i::new(constants.width, constants.height));
-	std::fs::create_dir("images").map_err(|err| 
-		if err.kind() == AlreadyExists {
-			Ok(())
-		} else {
-			Err(err)
-		}
-	).unwrap();
-	
-	//let mut image_scaled = Image::new(&(Vec2i::new(constants.width, constants.height) / 2));
-
-	struct BigColor {
-		r: u32,
-		g: u32,
-		b: u32,
-		a: u32,
+	let mut frame = Frame::new(&constants.size(), block);
+
+	let mut writer = match format {
+		Format::Png => {
+			std::fs::create_dir_all(output).map_err(|e| format!("Can't create directory {}: {}", output.display(), e))?;
+			None
+		},
+		Format::Y4m | Format::Raw => {
+			let file = File::create(output).map_err(|e| format!("Can't create file {}: {}", output.display(), e))?;
+			Some(BufWriter::new(file))
+		},
+	};
+	if let (Format::Y4m, Some(writer)) = (&format, &mut writer) {
+		writeln!(writer, "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444", frame.size.x, frame.size.y, fps).map_err(|e| e.to_string())?;
 	}
 
-	let width_scaled = 200;
-	let height_scaled = 200;
-	let image_scaled = Vec::new(width_scaled * height_scaled);
+	let genomes = match &constants.genomes {
+		Some(path) => read_genomes(Path::new(path), &constants)?,
+		None => Vec::new(),
+	};
+	let mut world = init_world_from_genomes(&constants, &mut rng, grid, &genomes);
+	let mut frames = 0;
+	let start = std::time::Instant::now();
+	for i in 0..steps {
+		process_world(constants.rules.ruleset(), &constants, &mut rng, &mut world, &mut ());
+		if i % skip != 0 {
+			continue;
+		}
+
+		frame.clear();
+		for (pos, bot) in world.bots.iter() {
+			frame.add(&pos, &bot.color);
+		}
 
-	time(|clock| {
-		let mut world = init_world(&constants, &mut rng, grid);
-		for i in 0..steps {
-			bots += world.bots.len();
-			process_world(&constants, &mut rng, &mut world);
+		match (&format, &mut writer) {
+			(Format::Png, _) => frame.save_png(&output.join(format!("{:06}.png", frames)))?,
+			(Format::Y4m, Some(writer)) => frame.write_y4m(writer).map_err(|e| e.to_string())?,
+			(Format::Raw, Some(writer)) => frame.write_raw(writer).map_err(|e| e.to_string())?,
+			_ => unreachable!(),
+		}
+		frames += 1;
 
-			image.clear(&Color::gray(0));
-			for (pos, bot) in world.bots.iter() {
-				set_pixel(&mut image, &pos, &bot.color);
-			}
+		println!("#{}, bots: {}, steps per second: {:.1}", i, world.bots.len(), (i + 1) as f64 / start.elapsed().as_secs_f64());
+	}
+	if let Some(writer) = &mut writer {
+		writer.flush().map_err(|e| e.to_string())?;
+	}
 
-			image.save_png(&std::path::Path::new(&format!("images/{}.png", i))).expect("Cant save image");
+	if let Some(path) = matches.value_of("export_genomes") {
+		let count = write_surviving_genomes(&world, Path::new(path))?;
+		println!("{} genomes are written to {}", count, path);
+	}
 
-			println!("#{}, fps: {}", i, clock.elapsed().fps() * i as f64);
-		}
-	});
-}
\ No newline at end of file
+	println!("{} frames of {}×{} written in {:.2} seconds", frames, frame.size.x, frame.size.y, start.elapsed().as_secs_f64());
+	if let Format::Raw = format {
+		println!(
+			"Convert with: ffmpeg -f rawvideo -pixel_format rgb24 -video_size {}x{} -framerate {} -i {} out.mp4",
+			frame.size.x, frame.size.y, fps, output.display()
+		);
+	}
+
+	Ok(())
+}
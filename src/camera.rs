@@ -1,4 +1,4 @@
-use bufdraw::vec::*;
+use crate::{Vec2i, MIN_SCALE};
 
 #[derive(Clone)]
 pub struct FloatImageCamera {
@@ -8,7 +8,14 @@ pub struct FloatImageCamera {
 
 impl FloatImageCamera {
 	pub fn to(&self, pos: Vec2i) -> Vec2i {
-		(pos - &self.offset) * self.scale
+		let (x, y) = self.to_f(pos);
+		Vec2i::new(x.floor() as i32, y.floor() as i32)
+	}
+
+	/// Same as `to`, but without rounding to the cell
+	pub fn to_f(&self, pos: Vec2i) -> (f32, f32) {
+		let pos = pos - &self.offset;
+		(pos.x as f32 / self.scale, pos.y as f32 / self.scale)
 	}
 
 	pub fn from(&self, pos: Vec2i) -> Vec2i {
@@ -20,12 +27,19 @@ impl FloatImageCamera {
 		dir * self.scale
 	}
 
-	pub fn from_i(&self, pos: Vec2i) -> Vec2i {
-		pos * (self.scale as i32) + &self.offset
+	/// Same as `from`, but without rounding to the pixel
+	pub fn from_f(&self, (x, y): (f32, f32)) -> (f32, f32) {
+		(x * self.scale + self.offset.x as f32, y * self.scale + self.offset.y as f32)
 	}
 
-	pub fn from_dir_i(&self, dir: Vec2i) -> Vec2i {
-		dir * (self.scale as i32)
+	/// Position and size on the screen of the cell with top left corner at `pos`. Neighbouring cells don't overlap and don't leave gaps, so with fractional scale sizes of cells differ by one pixel.
+	pub fn cell_rect(&self, (x, y): (f32, f32)) -> (Vec2i, Vec2i) {
+		let (x1, y1) = self.from_f((x, y));
+		let (x2, y2) = self.from_f((x + 1.0, y + 1.0));
+		let start = Vec2i::new(x1.floor() as i32, y1.floor() as i32);
+		let end = Vec2i::new(x2.floor() as i32, y2.floor() as i32);
+		let size = end - &start;
+		(start, size)
 	}
 
 	pub fn offset(&mut self, offset: &Vec2i) {
@@ -46,6 +60,7 @@ impl FloatImageCamera {
 
 	pub fn scale_mul(&mut self, mouse_pos: &Vec2i, mul_to_scale: f32) {
 		if self.scale * mul_to_scale > 256.0 { return; }
+		if self.scale * mul_to_scale < MIN_SCALE { return; }
 
 		self.scale_new(mouse_pos, self.scale * mul_to_scale);
 	}
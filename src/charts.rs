@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+use crate::*;
+
+/// Count of ticks shown on charts, one pixel per tick
+pub const CHART_LEN: usize = 200;
+#[cfg(feature = "gui")]
+const CHART_HEIGHT: i32 = 40;
+
+#[cfg(feature = "gui")]
+const ACTIONS: [Comands; 5] = [Comands::Multiply, Comands::Photosynthesis, Comands::Attack, Comands::Food, Comands::Move];
+
+/// Counts successful actions of creatures, indexed by `Comands`
+#[derive(Clone, Default)]
+pub struct ActionCounter {
+	pub actions: [u32; 5],
+}
+
+impl<C> Observer<C> for ActionCounter {
+	fn birth(&mut self, _parent_pos: &Vec2i, _parent: &C, _child_pos: &Vec2i, _child: &C) {
+		self.actions[Comands::Multiply as usize] += 1;
+	}
+	fn attack(&mut self, _attacker_pos: &Vec2i, _attacker: &C, _victim_pos: &Vec2i, _victim: &C, _amount: u32) {
+		self.actions[Comands::Attack as usize] += 1;
+	}
+	fn movement(&mut self, _from: &Vec2i, _to: &Vec2i, _bot: &C) {
+		self.actions[Comands::Move as usize] += 1;
+	}
+	fn photosynthesis(&mut self, _pos: &Vec2i, _bot: &C) {
+		self.actions[Comands::Photosynthesis as usize] += 1;
+	}
+	fn food(&mut self, _pos: &Vec2i, _bot: &C) {
+		self.actions[Comands::Food as usize] += 1;
+	}
+}
+
+/// State of the world after one tick
+pub struct Sample {
+	pub bots: usize,
+	pub free_protein: u32,
+	pub oxygen: u32,
+	pub carbon: u32,
+	pub actions: ActionCounter,
+}
+
+/// Last `CHART_LEN` samples
+#[derive(Default)]
+pub struct History {
+	pub samples: VecDeque<Sample>,
+}
+
+impl History {
+	pub fn push<C, G: Grid<C>>(&mut self, world: &World<G>, actions: ActionCounter) {
+		if self.samples.len() == CHART_LEN {
+			self.samples.pop_front();
+		}
+		self.samples.push_back(Sample {
+			bots: world.bots.len(),
+			free_protein: world.resources.free_protein,
+			oxygen: world.resources.oxygen,
+			carbon: world.resources.carbon,
+			actions,
+		});
+	}
+
+	/// Values of one quantity from the oldest sample to the newest
+	pub fn series<F: Fn(&Sample) -> u64>(&self, f: F) -> Vec<u64> {
+		self.samples.iter().map(f).collect()
+	}
+}
+
+#[cfg(feature = "gui")]
+/// Draws charts one under another, returns size of the occupied place
+pub(crate) fn draw_charts(image: &mut Image, text_cache: &mut TextCache, history: &History, pos: &Vec2i) -> Vec2i {
+	let gray = Color::rgba(200, 200, 200, 255);
+	let charts = vec![
+		("bots", vec![(gray.clone(), history.series(|s| s.bots as u64))]),
+		("free protein", vec![(gray.clone(), history.series(|s| u64::from(s.free_protein)))]),
+		("oxygen", vec![(gray.clone(), history.series(|s| u64::from(s.oxygen)))]),
+		("carbon", vec![(gray, history.series(|s| u64::from(s.carbon)))]),
+		("actions per tick", ACTIONS.iter()
+			.map(|comand| (comand_color(*comand), history.series(|s| u64::from(s.actions.actions[*comand as usize]))))
+			.collect()),
+	];
+
+	let mut size = Vec2i::default();
+	for (title, series) in &charts {
+		let chart_size = draw_chart(image, text_cache, title, series, &Vec2i::new(pos.x, pos.y + size.y));
+		size.x = std::cmp::max(size.x, chart_size.x);
+		size.y += chart_size.y + 3;
+	}
+	size
+}
+
+#[cfg(feature = "gui")]
+/// Draws title with the last value and line of each series scaled to their common maximum
+fn draw_chart(image: &mut Image, text_cache: &mut TextCache, title: &str, series: &[(Color, Vec<u64>)], pos: &Vec2i) -> Vec2i {
+	let max = series.iter().flat_map(|(_, values)| values.iter()).cloned().max().unwrap_or(0);
+	let text = match series {
+		[(_, values)] => format!("{}: {}, max {}", title, values.last().cloned().unwrap_or(0), max),
+		_ => format!("{}, max {}", title, max),
+	};
+	let text_height = text_size(text_cache, &text, HUD_TEXT_SIZE).y;
+	let size = Vec2i::new(CHART_LEN as i32 + 2 * HUD_BORDER, text_height + CHART_HEIGHT + 3 * HUD_BORDER);
+	draw_rect(image, pos, &size, &Color::rgba(0, 0, 0, 150));
+	draw_text(image, text_cache, &text, HUD_TEXT_SIZE, &(pos.clone() + &Vec2i::new(HUD_BORDER, HUD_BORDER)), &Color::rgba(255, 255, 255, 255));
+
+	let bottom = pos.y + size.y - HUD_BORDER - 1;
+	let height = |value: u64| (value * (CHART_HEIGHT - 1) as u64 / std::cmp::max(max, 1)) as i32;
+	for (color, values) in series {
+		let mut previous = None;
+		for (i, value) in values.iter().enumerate() {
+			let y = bottom - height(*value);
+			// Vertical segment connects the point with the previous one
+			let (from, to) = match previous {
+				Some(previous) => (std::cmp::min(previous, y), std::cmp::max(previous, y)),
+				None => (y, y),
+			};
+			rect(image, &Vec2i::new(pos.x + HUD_BORDER + i as i32, from), &Vec2i::new(1, to - from + 1), color);
+			previous = Some(y);
+		}
+	}
+	size
+}
@@ -0,0 +1,60 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Color {
+	pub r: u8,
+	pub g: u8,
+	pub b: u8,
+	pub a: u8,
+}
+
+impl Color {
+	pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+		Color { r, g, b, a }
+	}
+
+	pub fn gray(value: u8) -> Self {
+		Color::rgba(value, value, value, 255)
+	}
+}
+
+/// Sum of colors to average them, alpha is ignored
+#[derive(Clone, Default, Debug)]
+pub struct ColorSum {
+	pub r: u32,
+	pub g: u32,
+	pub b: u32,
+}
+
+impl ColorSum {
+	pub fn add(&mut self, color: &Color) {
+		self.r += u32::from(color.r);
+		self.g += u32::from(color.g);
+		self.b += u32::from(color.b);
+	}
+
+	/// Average color, when `count` colors are summed and missing ones are black
+	pub fn average(&self, count: u32) -> Color {
+		Color::rgba((self.r / count) as u8, (self.g / count) as u8, (self.b / count) as u8, 255)
+	}
+}
+
+/// Linear interpolation, `t` is from 0 for `self` to 1 for `other`.
+pub trait Interpolate {
+	fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for u8 {
+	fn interpolate(&self, other: &Self, t: f32) -> Self {
+		(*self as f32 + (*other as f32 - *self as f32) * t) as u8
+	}
+}
+
+impl Interpolate for Color {
+	fn interpolate(&self, other: &Self, t: f32) -> Self {
+		Color::rgba(
+			self.r.interpolate(&other.r, t),
+			self.g.interpolate(&other.g, t),
+			self.b.interpolate(&other.b, t),
+			self.a.interpolate(&other.a, t),
+		)
+	}
+}
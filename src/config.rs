@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::*;
+
+/// Names of values in configuration files, same as names of command line arguments
+pub const CONFIG_KEYS: [&str; 21] = [
+	"width", "height", "scale", "image_scale", "benchmark",
+	"bots", "protein", "oxygen", "carbon",
+	"die", "live", "comand", "multiply", "seed",
+	"topology", "container", "neighborhood", "sense", "update", "rules",
+	"genomes",
+];
+
+/// Why the configuration can't be used.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConfigError {
+	/// Wrong command line, also help and version messages
+	Arguments(String),
+	/// Config file can't be read or written
+	Io { path: String, message: String },
+	/// Extension of the config file is neither `.toml` nor `.json`
+	UnknownFormat { path: String },
+	/// Config file is not valid TOML or JSON, or has nested values
+	Syntax { path: String, message: String },
+	UnknownKey { path: String, key: String },
+	/// Value can't be parsed into the type of the field
+	Parse { key: String, value: String, message: String },
+	/// Value of the field is out of range
+	Invalid { key: &'static str, message: String },
+	/// Values of several fields can't be used together
+	Incompatible { keys: Vec<&'static str>, message: String },
+}
+
+impl std::fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		use ConfigError::*;
+		match self {
+			Arguments(message) => write!(f, "{}", message),
+			Io { path, message } => write!(f, "Can't access config {}:\n\t{}", path, message),
+			UnknownFormat { path } => write!(f, "Config {} should have .toml or .json extension", path),
+			Syntax { path, message } => write!(f, "Error in config {}:\n\t{}", path, message),
+			UnknownKey { path, key } => write!(f, "Error in config {}:\n\tunknown key `{}`, keys can only be: {:?}", path, key, CONFIG_KEYS),
+			Parse { key, value, message } => write!(f, "Error occured while parsing arguments:\n\t{}\n\nYou provided:\n\t{}={}", message, key, value),
+			Invalid { key, message } => write!(f, "Wrong value of {}:\n\t{}", key, message),
+			Incompatible { keys, message } => write!(f, "Values of {} can't be used together:\n\t{}", keys.join(", "), message),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for String {
+	fn from(error: ConfigError) -> String {
+		error.to_string()
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ConfigFormat {
+	Toml,
+	Json,
+}
+
+impl ConfigFormat {
+	/// Format by extension of the file, only `.toml` and `.json` are known
+	pub fn from_path(path: &Path) -> Result<Self, ConfigError> {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("toml") => Ok(ConfigFormat::Toml),
+			Some("json") => Ok(ConfigFormat::Json),
+			_ => Err(ConfigError::UnknownFormat { path: path.display().to_string() }),
+		}
+	}
+}
+
+/// Reads values from the configuration file as strings, so they are parsed the same way as command line arguments. Any subset of `CONFIG_KEYS` can be present.
+pub fn read_config(path: &Path) -> Result<HashMap<String, String>, ConfigError> {
+	let path_string = path.display().to_string();
+	let format = ConfigFormat::from_path(path)?;
+	let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Io { path: path_string.clone(), message: e.to_string() })?;
+	let values = parse_config(&text, format).map_err(|message| ConfigError::Syntax { path: path_string.clone(), message })?;
+	for key in values.keys() {
+		if !CONFIG_KEYS.contains(&key.as_str()) {
+			return Err(ConfigError::UnknownKey { path: path_string, key: key.clone() });
+		}
+	}
+	Ok(values)
+}
+
+pub fn parse_config(text: &str, format: ConfigFormat) -> Result<HashMap<String, String>, String> {
+	match format {
+		ConfigFormat::Toml => {
+			let table: toml::value::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+			table.into_iter().map(|(key, value)| {
+				let value = match value {
+					toml::Value::String(value) => value,
+					toml::Value::Integer(value) => value.to_string(),
+					toml::Value::Float(value) => value.to_string(),
+					toml::Value::Boolean(value) => value.to_string(),
+					_ => return Err(format!("value of `{}` should be a string, number or boolean", key)),
+				};
+				Ok((key, value))
+			}).collect()
+		},
+		ConfigFormat::Json => {
+			let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+			map.into_iter().map(|(key, value)| {
+				let value = match value {
+					serde_json::Value::String(value) => value,
+					serde_json::Value::Number(value) => value.to_string(),
+					serde_json::Value::Bool(value) => value.to_string(),
+					_ => return Err(format!("value of `{}` should be a string, number or boolean", key)),
+				};
+				Ok((key, value))
+			}).collect()
+		},
+	}
+}
+
+pub fn config_to_string(constants: &Constants, format: ConfigFormat) -> Result<String, String> {
+	match format {
+		ConfigFormat::Toml => toml::to_string(constants).map_err(|e| e.to_string()),
+		ConfigFormat::Json => serde_json::to_string_pretty(constants).map_err(|e| e.to_string()),
+	}
+}
+
+/// Writes fully resolved configuration, it can be read back with `--config`
+pub fn write_config(constants: &Constants, path: &Path) -> Result<(), ConfigError> {
+	let io_error = |message: String| ConfigError::Io { path: path.display().to_string(), message };
+	let text = config_to_string(constants, ConfigFormat::from_path(path)?).map_err(io_error)?;
+	std::fs::write(path, text).map_err(|e| io_error(e.to_string()))
+}
+
+impl Constants {
+	/// Checks values which would cause panics or meaningless simulation. Fields not mentioned here accept any value: `benchmark`, `bots`, `die`, `seed`, `update`, `rules`, `genomes`.
+	pub fn validate(&self) -> Result<(), ConfigError> {
+		let invalid = |key: &'static str, message: &str| Err(ConfigError::Invalid { key, message: message.to_string() });
+		let incompatible = |keys: &[&'static str], message: &str| Err(ConfigError::Incompatible { keys: keys.to_vec(), message: message.to_string() });
+
+		if self.width <= 0 {
+			return invalid("width", "should be positive");
+		}
+		if self.height <= 0 {
+			return invalid("height", "should be positive");
+		}
+		if !(MIN_SCALE..=256.0).contains(&self.scale) {
+			return invalid("scale", &format!("should be from {} to 256", MIN_SCALE));
+		}
+		if self.image_scale == 0 {
+			return invalid("image_scale", "should be positive");
+		}
+		if self.live == 0 {
+			return invalid("live", "should be positive, otherwise bots die right after birth");
+		}
+		if self.comand == 0 {
+			return invalid("comand", "should be positive, otherwise bots do nothing");
+		}
+		if self.multiply == 0 || self.multiply > u32::MAX / 10 {
+			return invalid("multiply", &format!("should be from 1 to {}", u32::MAX / 10));
+		}
+		// Attack looks through (2 * sense + 1)² cells, larger radius only wraps around the world
+		let max_sense = self.width.max(self.height) as u32;
+		if self.sense == 0 || self.sense > max_sense {
+			return invalid("sense", &format!("should be from 1 to {}, the larger side of the world", max_sense));
+		}
+
+		// Resources only move between bots and the world, so their sum never grows
+		if u64::from(self.protein) + u64::from(self.oxygen) + u64::from(self.carbon) > u64::from(u32::MAX) {
+			return incompatible(&["protein", "oxygen", "carbon"], &format!("sum of all resources should be at most {}", u32::MAX));
+		}
+
+		use FieldTopology::*;
+		if let (FieldContainer::Vec, Infinite) = (&self.container, &self.topology) {
+			return incompatible(&["container", "topology"], "Vec can't hold infinite space, use HashMap or Chunked instead");
+		}
+		if let FieldContainer::Vec = self.container {
+			if i64::from(self.width) * i64::from(self.height) > i64::from(i32::MAX) {
+				return incompatible(&["container", "width", "height"], "world is too big for Vec, use HashMap or Chunked instead");
+			}
+		}
+		// Parity of the row defines hexagonal neighbors, so it should be kept when crossing the border
+		match (&self.neighborhood, &self.topology) {
+			(FieldNeighborhood::Hexagonal, Torus) | (FieldNeighborhood::Hexagonal, VerticalCylinder) if self.height % 2 != 0 =>
+				incompatible(&["neighborhood", "topology", "height"], "hexagonal lattice needs even height when space is repeated by y"),
+			(FieldNeighborhood::Hexagonal, Mobius) if self.height % 2 == 0 =>
+				incompatible(&["neighborhood", "topology", "height"], "hexagonal lattice needs odd height on Mobius strip, because crossing the border mirrors rows"),
+			(FieldNeighborhood::Hexagonal, KleinBottle) =>
+				incompatible(&["neighborhood", "topology"], "hexagonal lattice can't be placed on Klein bottle: repeating by y needs even height, mirroring needs odd height"),
+			_ => Ok(()),
+		}
+	}
+}
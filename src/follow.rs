@@ -0,0 +1,54 @@
+use crate::*;
+
+/// What the camera keeps in the center of the screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Follow {
+	Off,
+	/// Selected creature
+	Bot,
+	/// Average position of all creatures with this lineage
+	Lineage(u32),
+}
+
+/// Copy of the cell nearest to `to` among the repeated copies of the world, as they are drawn on the screen
+pub fn nearest_copy<C, G: Grid<C>>(world: &World<G>, pos: &Vec2i, to: (f32, f32)) -> (f32, f32) {
+	let size = &world.size;
+	let tiles = |repeat: bool, to: f32, len: i32| {
+		let tile = (to / len as f32).floor() as i32;
+		if repeat { tile - 1..=tile + 1 } else { 0..=0 }
+	};
+	let mut nearest = (pos.x as f32, pos.y as f32);
+	let mut nearest_distance = f32::MAX;
+	for tile_x in tiles(world.bots.is_repeat_x(), to.0, size.x) {
+		for tile_y in tiles(world.bots.is_repeat_y(), to.1, size.y) {
+			let y = if world.bots.is_mirrored_x() && tile_x & 1 == 1 { size.y - 1 - pos.y } else { pos.y };
+			let copy = ((pos.x + tile_x * size.x) as f32, (y + tile_y * size.y) as f32);
+			let distance = (copy.0 - to.0).powi(2) + (copy.1 - to.1).powi(2);
+			if distance < nearest_distance {
+				nearest = copy;
+				nearest_distance = distance;
+			}
+		}
+	}
+	nearest
+}
+
+/// Point in the world to put in the center of the screen, `None` if there is nothing to follow
+pub fn follow_target<C: WindowCreature, G: Grid<C>>(world: &World<G>, follow: Follow, selected: Option<&Vec2i>, center: (f32, f32)) -> Option<(f32, f32)> {
+	let (x, y) = match follow {
+		Follow::Off => return None,
+		Follow::Bot => nearest_copy(world, selected?, center),
+		Follow::Lineage(lineage) => {
+			let (sum_x, sum_y, count) = world.bots.iter()
+				.filter(|(_, creature)| creature.lineage() == Some(lineage))
+				.map(|(pos, _)| nearest_copy(world, &pos, center))
+				.fold((0.0, 0.0, 0), |(sum_x, sum_y, count), (x, y)| (sum_x + x, sum_y + y, count + 1));
+			if count == 0 {
+				return None;
+			}
+			(sum_x / count as f32, sum_y / count as f32)
+		},
+	};
+	// Center of the cell
+	Some((x + 0.5, y + 0.5))
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::Path;
+use rand::Rng;
+use crate::*;
+
+/// Why the text can't be read as a program.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GenomeError {
+	/// Line doesn't match `<index>: <comand> ok-><index> fail-><index>`
+	Syntax { line: usize, message: String },
+	/// Jump to the comand outside of the program
+	OutOfBounds { line: usize, pos: usize },
+	/// Count of comands is not `PROGRAM_SIZE`, `line` is the line of the last comand
+	Length { line: usize, count: usize },
+}
+
+impl std::fmt::Display for GenomeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		use GenomeError::*;
+		match self {
+			Syntax { line, message } => write!(f, "Error in genome at line {}:\n\t{}", line, message),
+			OutOfBounds { line, pos } => write!(f, "Error in genome at line {}:\n\tjump to {} is outside of the program, indexes can only be from 0 to {}", line, pos, PROGRAM_SIZE - 1),
+			Length { line, count } => write!(f, "Error in genome at line {}:\n\tgenome should have {} comands, found {}", line, PROGRAM_SIZE, count),
+		}
+	}
+}
+
+impl GenomeError {
+	/// Same error for the program which starts at line `first_line` of a bigger text
+	fn shifted(self, first_line: usize) -> Self {
+		use GenomeError::*;
+		let shift = |line: usize| line + first_line - 1;
+		match self {
+			Syntax { line, message } => Syntax { line: shift(line), message },
+			OutOfBounds { line, pos } => OutOfBounds { line: shift(line), pos },
+			Length { line, count } => Length { line: shift(line), count },
+		}
+	}
+}
+
+impl std::error::Error for GenomeError {}
+
+impl From<GenomeError> for String {
+	fn from(error: GenomeError) -> String {
+		error.to_string()
+	}
+}
+
+/// Format of one line of the program without the index: `Photosynthesis ok->1 fail->3`
+impl std::fmt::Display for Comand {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{:?} ok->{} fail->{}", self.comand, self.goto_success.0, self.goto_fail.0)
+	}
+}
+
+/// One comand per line: `0: Photosynthesis ok->1 fail->3`
+pub fn program_to_string(program: &Program) -> String {
+	program.iter()
+		.enumerate()
+		.map(|(i, comand)| format!("{}: {}\n", i, comand))
+		.collect()
+}
+
+/// Reads the format of `program_to_string`. Empty lines and everything after `#` are ignored, comands should be numbered from 0 in order.
+pub fn parse_program(text: &str) -> Result<Program, GenomeError> {
+	let mut comands = Vec::new();
+	let mut last_line = text.lines().count().max(1);
+	for (i, line) in text.lines().enumerate() {
+		let line_number = i + 1;
+		let line = line.split('#').next().unwrap_or("").trim();
+		if line.is_empty() {
+			continue;
+		}
+		last_line = line_number;
+		let syntax = |message: String| GenomeError::Syntax { line: line_number, message };
+
+		let (index, comand) = line.split_once(':')
+			.ok_or_else(|| syntax("expected `<index>: <comand> ok-><index> fail-><index>`".to_string()))?;
+		let index = index.trim();
+		if index.parse() != Ok(comands.len()) {
+			return Err(syntax(format!("expected index {}, found `{}`", comands.len(), index)));
+		}
+
+		let words: Vec<&str> = comand.split_whitespace().collect();
+		let (name, ok, fail) = match words.as_slice() {
+			[name, ok, fail] => (*name, *ok, *fail),
+			_ => return Err(syntax(format!("expected `<comand> ok-><index> fail-><index>`, found `{}`", comand.trim()))),
+		};
+		let comand = name.parse::<Comands>()
+			.map_err(|_| syntax(format!("unknown comand `{}`, comands can only be: {:?}", name, Comands::iter().collect::<Vec<_>>())))?;
+		let target = |word: &str, prefix: &str| {
+			let pos: usize = word.strip_prefix(prefix)
+				.and_then(|pos| pos.parse().ok())
+				.ok_or_else(|| syntax(format!("expected `{}<index>`, found `{}`", prefix, word)))?;
+			ProgramPos::new(pos).ok_or(GenomeError::OutOfBounds { line: line_number, pos })
+		};
+		comands.push(Comand {
+			comand,
+			goto_success: target(ok, "ok->")?,
+			goto_fail: target(fail, "fail->")?,
+		});
+	}
+	Program::try_from(comands.as_slice()).map_err(|_| GenomeError::Length { line: last_line, count: comands.len() })
+}
+
+/// Rectangle of the world where bots of the genome are placed
+#[derive(Clone, PartialEq, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Region {
+	pub x: i32,
+	pub y: i32,
+	pub width: i32,
+	pub height: i32,
+}
+
+impl Region {
+	/// Finite worlds would wrap cells outside of them to the opposite edge, so the region should be inside
+	fn check(&self, constants: &Constants) -> Result<(), String> {
+		if self.x < 0 || self.y < 0 || self.width <= 0 || self.height <= 0 {
+			return Err("region should have positive size and not negative position".to_string());
+		}
+		let (width, height) = match constants.topology {
+			FieldTopology::Infinite => (i32::MAX, i32::MAX),
+			_ => (constants.width, constants.height),
+		};
+		if i64::from(self.x) + i64::from(self.width) > i64::from(width) || i64::from(self.y) + i64::from(self.height) > i64::from(height) {
+			return Err(format!("region should be inside the world of {}×{} cells", width, height));
+		}
+		Ok(())
+	}
+}
+
+/// Genome and how many bots with it are placed into the initial population
+#[derive(Clone, PartialEq, Debug)]
+pub struct GenomeEntry {
+	pub program: Program,
+	pub count: usize,
+	/// All bots of the genome have this color, random if not set
+	pub color: Option<Color>,
+	/// Whole world if not set
+	pub region: Option<Region>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenomeLibrary {
+	genome: Vec<GenomeTable>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenomeTable {
+	program: toml::Spanned<String>,
+	count: usize,
+	color: Option<[u8; 3]>,
+	region: Option<toml::Spanned<Region>>,
+}
+
+/// Reads TOML with `[[genome]]` tables, `program` is in the format of `program_to_string`. Regions are checked against the world of `constants`:
+///
+/// ```toml
+/// [[genome]]
+/// count = 50
+/// color = [0, 200, 0]
+/// region = { x = 0, y = 0, width = 40, height = 40 }
+/// program = """
+/// 0: Photosynthesis ok->0 fail->1
+/// ...
+/// """
+/// ```
+pub fn parse_genomes(text: &str, constants: &Constants) -> Result<Vec<GenomeEntry>, String> {
+	let library: GenomeLibrary = toml::from_str(text).map_err(|e| e.to_string())?;
+	library.genome.into_iter().enumerate().map(|(i, table)| {
+		let first_line = first_line_of_string(text, table.program.start());
+		let program = parse_program(table.program.get_ref()).map_err(|e| format!("genome #{}, {}", i + 1, e.shifted(first_line)))?;
+		if let Some(region) = &table.region {
+			let line = text[..region.start()].matches('\n').count() + 1;
+			region.get_ref().check(constants).map_err(|message| format!("genome #{}, Error in genome at line {}:\n\t{}", i + 1, line, message))?;
+		}
+		Ok(GenomeEntry {
+			program,
+			count: table.count,
+			color: table.color.map(|[r, g, b]| Color::rgba(r, g, b, 255)),
+			region: table.region.map(toml::Spanned::into_inner),
+		})
+	}).collect()
+}
+
+/// Line of `text` with the first line of the string value which starts at byte `start`. Line break right after `"""` or `'''` is not a part of the value.
+fn first_line_of_string(text: &str, start: usize) -> usize {
+	let line = text[..start].matches('\n').count() + 1;
+	let value = &text[start..];
+	let skipped_break = ["\"\"\"\n", "\"\"\"\r\n", "'''\n", "'''\r\n"].iter().any(|opening| value.starts_with(opening));
+	if skipped_break { line + 1 } else { line }
+}
+
+pub fn read_genomes(path: &Path, constants: &Constants) -> Result<Vec<GenomeEntry>, String> {
+	let text = std::fs::read_to_string(path).map_err(|e| format!("Can't read genomes {}:\n\t{}", path.display(), e))?;
+	parse_genomes(&text, constants).map_err(|message| format!("Error in genomes {}:\n\t{}", path.display(), message))
+}
+
+/// Free cells are searched randomly, so crowded region can get less bots than needed
+const PLACE_ATTEMPTS: usize = 100;
+
+fn place_bot_in<R: Rng + ?Sized, G: Grid<Bot>>(rng: &mut R, world: &mut World<G>, mut bot: Bot, region: Option<&Region>) -> bool {
+	for _ in 0..PLACE_ATTEMPTS {
+		// Region is inside the world, see `Region::check`
+		let pos = match region {
+			Some(region) => Vec2i::new(region.x + rng.gen_range(0, region.width), region.y + rng.gen_range(0, region.height)),
+			None => normalize_coords(Vec2i::new(rng.gen(), rng.gen()), &world.size),
+		};
+		if world.bots.can(&pos) && !world.bots.has(&pos) {
+			world.bots.set_unchecked(&pos, bot);
+			return true;
+		}
+	}
+	bot.decompose(&mut world.resources);
+	false
+}
+
+/// Places bots of every genome, each genome gets its own lineage. If they are less than `constants.bots`, the rest is random. Bots are put only into free cells, so random ones never replace bots of genomes.
+pub fn init_world_from_genomes<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R, g: G, genomes: &[GenomeEntry]) -> World<G> {
+	let mut world = empty_world(constants, g);
+	let mut placed = 0;
+	for genome in genomes {
+		let color = genome.color.clone().unwrap_or_else(|| Color::make_random(rng));
+		let lineage = world.next_lineage;
+		world.next_lineage += 1;
+		for _ in 0..genome.count {
+			let mut bot = Bot {
+				color: color.clone(),
+				program: genome.program,
+				..Bot::make_random(rng)
+			};
+			bot.init(constants, lineage);
+			if place_bot_in(rng, &mut world, bot, genome.region.as_ref()) {
+				placed += 1;
+			}
+		}
+	}
+
+	for _ in placed..constants.bots {
+		insert_random_bot(constants, rng, &mut world);
+	}
+
+	world
+}
+
+/// Distinct genome of alive bots
+#[derive(Clone, PartialEq, Debug)]
+pub struct SurvivingGenome {
+	pub program: Program,
+	pub count: usize,
+	/// Average color of bots with this genome
+	pub color: Color,
+	pub average_protein: f32,
+	/// Sorted ids of random bots this genome descended from
+	pub lineages: Vec<u32>,
+}
+
+/// Most common genomes first
+pub fn surviving_genomes<G: Grid<Bot>>(world: &World<G>) -> Vec<SurvivingGenome> {
+	#[derive(Default)]
+	struct Sum {
+		count: usize,
+		color: ColorSum,
+		protein: u64,
+		lineages: Vec<u32>,
+	}
+
+	let mut sums: HashMap<Program, Sum> = HashMap::new();
+	for (_, bot) in world.bots.iter().filter(|(_, bot)| bot.alive) {
+		let sum = sums.entry(bot.program).or_default();
+		sum.count += 1;
+		sum.color.add(&bot.color);
+		sum.protein += u64::from(bot.protein);
+		sum.lineages.push(bot.lineage);
+	}
+
+	let mut genomes: Vec<SurvivingGenome> = sums.into_iter().map(|(program, mut sum)| {
+		sum.lineages.sort_unstable();
+		sum.lineages.dedup();
+		SurvivingGenome {
+			program,
+			count: sum.count,
+			color: sum.color.average(sum.count as u32),
+			average_protein: sum.protein as f32 / sum.count as f32,
+			lineages: sum.lineages,
+		}
+	}).collect();
+	genomes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.program.cmp(&b.program)));
+	genomes
+}
+
+/// Can be read back by `parse_genomes`, protein and lineages are written as comments
+pub fn surviving_genomes_to_string(genomes: &[SurvivingGenome]) -> String {
+	let mut text = format!("# {} distinct genomes of {} alive bots\n", genomes.len(), genomes.iter().map(|genome| genome.count).sum::<usize>());
+	for genome in genomes {
+		let lineages: Vec<String> = genome.lineages.iter().map(|lineage| lineage.to_string()).collect();
+		text += &format!(
+			"\n[[genome]]\n# average protein: {:.1}, lineages: {}\ncount = {}\ncolor = [{}, {}, {}]\nprogram = \"\"\"\n{}\"\"\"\n",
+			genome.average_protein,
+			lineages.join(", "),
+			genome.count,
+			genome.color.r, genome.color.g, genome.color.b,
+			program_to_string(&genome.program),
+		);
+	}
+	text
+}
+
+/// Returns count of written genomes
+pub fn write_surviving_genomes<G: Grid<Bot>>(world: &World<G>, path: &Path) -> Result<usize, String> {
+	let genomes = surviving_genomes(world);
+	std::fs::write(path, surviving_genomes_to_string(&genomes)).map_err(|e| format!("Can't write genomes {}:\n\t{}", path.display(), e))?;
+	Ok(genomes.len())
+}
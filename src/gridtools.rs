@@ -1,6 +1,7 @@
-use bufdraw::vec::next_in_rect;
 use std::collections::HashMap;
-use bufdraw::vec::Vec2i;
+use std::collections::hash_map::Entry;
+use std::hash::{BuildHasherDefault, Hasher};
+use crate::Vec2i;
 
 pub trait MyIter<'b, T: 'b> {
 	type Iter: Iterator<Item = (Vec2i, &'b T)>;
@@ -11,11 +12,14 @@ pub trait MyIter<'b, T: 'b> {
 pub trait Grid<T>: for<'b> MyIter<'b, T> {
 	fn can(&self, pos: &Vec2i) -> bool;
 	fn has(&self, pos: &Vec2i) -> bool;
+	/// Position of the same cell inside the main copy of the world
+	fn remap(&self, pos: &Vec2i) -> Vec2i;
 
 	fn get<'a>(&'a self, pos: &Vec2i) -> Option<&'a T>;
 	fn get_mut<'a>(&'a mut self, pos: &Vec2i) -> Option<&'a mut T>;
 	fn get_owned(&mut self, pos: &Vec2i) -> Option<T>;
 
+	/// Puts `obj` only into the free existing cell, otherwise gives it back. Object already in the cell is never replaced.
 	fn set(&mut self, pos: &Vec2i, obj: T) -> Option<T>;
 	fn set_unchecked(&mut self, pos: &Vec2i, obj: T);
 
@@ -24,7 +28,32 @@ pub trait Grid<T>: for<'b> MyIter<'b, T> {
 
 	fn is_repeat_x(&self) -> bool;
 	fn is_repeat_y(&self) -> bool;
+	/// Crossing the border by x mirrors the world by y
+	fn is_mirrored_x(&self) -> bool;
 	fn is_finite(&self) -> bool;
+
+	/// Cells at `offsets` from `pos` that exist in this grid.
+	fn available_cells(&self, pos: &Vec2i, offsets: &[Vec2i]) -> Neighborhood {
+		offsets.iter()
+			.map(|offset| offset.clone() + pos)
+			.filter(|pos| self.can(pos))
+			.collect()
+	}
+
+	/// Empty cells around `pos`.
+	fn void_around(&self, pos: &Vec2i, offsets: &[Vec2i]) -> Neighborhood {
+		self.available_cells(pos, offsets).filter(|pos| !self.has(pos))
+	}
+
+	/// Filled cells around `pos` whose objects satisfy `f`.
+	fn filled_around<F: FnMut(&T) -> bool>(&self, pos: &Vec2i, offsets: &[Vec2i], mut f: F) -> Neighborhood {
+		self.available_cells(pos, offsets).filter(|pos| {
+			match self.get(pos) {
+				Some(obj) => f(obj),
+				None => false,
+			}
+		})
+	}
 }
 
 pub trait GridConstraints {
@@ -33,6 +62,8 @@ pub trait GridConstraints {
 
 	fn is_repeat_x(&self) -> bool;
 	fn is_repeat_y(&self) -> bool;
+	/// Crossing the border by x mirrors the world by y
+	fn is_mirrored_x(&self) -> bool;
 	fn is_finite(&self) -> bool;
 }
 
@@ -48,6 +79,14 @@ pub struct VerticalCylinderSpace {
 pub struct HorizontalCylinderSpace {
 	size: Vec2i,
 }
+/// Repeats by x, crossing the border by x mirrors the world by y
+pub struct MobiusSpace {
+	size: Vec2i,
+}
+/// Repeats by both axes, crossing the border by x mirrors the world by y
+pub struct KleinBottleSpace {
+	size: Vec2i,
+}
 
 #[derive(Default)]
 pub struct InfiniteSpace;
@@ -66,9 +105,8 @@ pub struct VecGrid<T, C> where
 }
 
 pub struct VecGridIterator<'a, T: 'a> {
-	iter: std::slice::Iter<'a, Option<T>>,
-	pos: Vec2i,
-	size: Vec2i,
+	iter: std::iter::Enumerate<std::slice::Iter<'a, Option<T>>>,
+	width: i32,
 }
 
 pub struct HashMapGrid<T, C> {
@@ -80,6 +118,37 @@ pub struct HashMapGridIterator<'a, T: 'a> {
 	iter: std::collections::hash_map::Iter<'a, Vec2i, T>,
 }
 
+/// Side of one square chunk in `ChunkGrid`.
+pub const CHUNK_SIZE: i32 = 32;
+
+/// Cheap deterministic hasher for chunk coordinates.
+#[derive(Default)]
+pub struct ChunkHasher(u64);
+
+type ChunkMap<T> = HashMap<Vec2i, Chunk<T>, BuildHasherDefault<ChunkHasher>>;
+
+struct Chunk<T> {
+	cells: Vec<Option<T>>,
+	count: usize,
+}
+
+/// Emptied chunks are kept until there are more of them than this and than non-empty chunks, so a bot moving back and forth doesn't reallocate its chunk every tick.
+const KEPT_EMPTY_CHUNKS: usize = 16;
+
+/// Sparse grid made of dense square chunks. Chunk is created when first object is placed into it, empty chunks are freed in batches, so memory is proportional to populated area.
+pub struct ChunkGrid<T, C> {
+	chunks: ChunkMap<T>,
+	constraints: C,
+	count: usize,
+	/// Count of chunks without objects
+	empty_chunks: usize,
+}
+
+pub struct ChunkGridIterator<'a, T: 'a> {
+	chunks: std::collections::hash_map::Iter<'a, Vec2i, Chunk<T>>,
+	current: Option<(Vec2i, std::iter::Enumerate<std::slice::Iter<'a, Option<T>>>)>,
+}
+
 /*pub const MOORE_DEPENDENT_NEIGHBORHOOD: [(Vec2i, Option<(Vec2i, Vec2i)>); 8] = [
 	(Vec2i { x: -1, y:  0 },  None),
 	(Vec2i { x:  1, y:  0 },  None),
@@ -103,12 +172,79 @@ pub const MOORE_NEIGHBORHOOD: [Vec2i; 8] = [
 	Vec2i { x: -1, y:  0 },
 ];
 
+pub const VON_NEUMANN_NEIGHBORHOOD: [Vec2i; 4] = [
+	Vec2i { x:  0, y:  1 },
+	Vec2i { x:  1, y:  0 },
+	Vec2i { x:  0, y: -1 },
+	Vec2i { x: -1, y:  0 },
+];
+
+/// Hexagonal lattice in "odd-r" layout: odd rows are shifted by half of the cell to the right. So neighbors depend on parity of the row, this is for even rows.
+pub const HEX_EVEN_ROW_NEIGHBORHOOD: [Vec2i; 6] = [
+	Vec2i { x: -1, y:  1 },
+	Vec2i { x:  0, y:  1 },
+	Vec2i { x:  1, y:  0 },
+	Vec2i { x:  0, y: -1 },
+	Vec2i { x: -1, y: -1 },
+	Vec2i { x: -1, y:  0 },
+];
+
+/// Same as `HEX_EVEN_ROW_NEIGHBORHOOD`, but for odd rows.
+pub const HEX_ODD_ROW_NEIGHBORHOOD: [Vec2i; 6] = [
+	Vec2i { x:  0, y:  1 },
+	Vec2i { x:  1, y:  1 },
+	Vec2i { x:  1, y:  0 },
+	Vec2i { x:  1, y: -1 },
+	Vec2i { x:  0, y: -1 },
+	Vec2i { x: -1, y:  0 },
+];
+
+/// Maximum count of cells that any neighborhood can return.
+pub const MAX_NEIGHBORHOOD: usize = 8;
+
+/// Cells around some position. Has fixed capacity and lives on the stack, so it can be built for every bot on every step without allocations.
+#[derive(Clone, Default)]
+pub struct Neighborhood {
+	cells: [Vec2i; MAX_NEIGHBORHOOD],
+	len: usize,
+}
+
+impl Neighborhood {
+	pub fn push(&mut self, pos: Vec2i) {
+		assert!(self.len < MAX_NEIGHBORHOOD);
+		self.cells[self.len] = pos;
+		self.len += 1;
+	}
+
+	pub fn filter<F: FnMut(&Vec2i) -> bool>(&self, mut f: F) -> Neighborhood {
+		self.iter().filter(|pos| f(pos)).cloned().collect()
+	}
+}
+
+impl std::ops::Deref for Neighborhood {
+	type Target = [Vec2i];
+
+	fn deref(&self) -> &[Vec2i] {
+		&self.cells[..self.len]
+	}
+}
+
+impl std::iter::FromIterator<Vec2i> for Neighborhood {
+	fn from_iter<I: IntoIterator<Item = Vec2i>>(iter: I) -> Self {
+		let mut result = Neighborhood::default();
+		for pos in iter {
+			result.push(pos);
+		}
+		result
+	}
+}
+
 fn rem_repeat_to_interval<'a, T>(min: &T, value: &T, max: &T) -> T where
 	T: 'a +  PartialOrd + Copy + std::ops::Sub<T, Output = T> + std::ops::Rem<T, Output = T>
 {
 	let len = *max - *min;
 	if value < min {
-		return len - (*min - *value) % len;
+		return (len - (*min - *value) % len) % len;
 	}
 	if value >= max {
 		return (*value - *min) % len;
@@ -116,6 +252,11 @@ fn rem_repeat_to_interval<'a, T>(min: &T, value: &T, max: &T) -> T where
 	*value
 }
 
+/// Returns `value` repeated to interval `[0, len)` and whether it crossed the border odd count of times
+fn rem_repeat_mirrored(value: i32, len: i32) -> (i32, bool) {
+	(value.rem_euclid(len), value.div_euclid(len) & 1 == 1)
+}
+
 /*pub fn available_dependent_cells<T, G: Grid<T>>(grid: &G, pos: &Vec2i) -> Vec<Vec2i> {
 	// TODOODODODO TODO TODO TODO
 	MOORE_DEPENDENT_NEIGHBORHOOD.iter().filter(|&(offset, dependency)| {
@@ -131,11 +272,8 @@ fn rem_repeat_to_interval<'a, T>(min: &T, value: &T, max: &T) -> T where
 	).map(|(offset, _)| offset.clone() + &pos).collect()
 }*/
 
-pub fn available_cells<T, G: Grid<T>>(grid: &G, pos: &Vec2i) -> Vec<Vec2i> {
-	MOORE_NEIGHBORHOOD.iter().filter(|&offset| {
-		grid.can(&(offset.clone() + pos))
-	}
-	).map(|offset| offset.clone() + pos).collect()
+pub fn available_cells<T, G: Grid<T>>(grid: &G, pos: &Vec2i, offsets: &[Vec2i]) -> Neighborhood {
+	grid.available_cells(pos, offsets)
 }
 
 impl CanFitInSize for RectSpace {
@@ -171,6 +309,23 @@ impl CanFitInSize for HorizontalCylinderSpace {
 	}
 }
 
+impl CanFitInSize for MobiusSpace {
+	fn new(size: &Vec2i) -> Self {
+		Self { size: size.clone() }
+	}
+	fn get_size(&self) -> Vec2i {
+		self.size.clone()
+	}
+}
+impl CanFitInSize for KleinBottleSpace {
+	fn new(size: &Vec2i) -> Self {
+		Self { size: size.clone() }
+	}
+	fn get_size(&self) -> Vec2i {
+		self.size.clone()
+	}
+}
+
 impl GridConstraints for RectSpace {
 	fn can(&self, pos: &Vec2i) -> bool {
 		0 <= pos.x && pos.x < self.size.x &&
@@ -187,6 +342,9 @@ impl GridConstraints for RectSpace {
 	fn is_repeat_y(&self) -> bool {
 		false
 	}
+	fn is_mirrored_x(&self) -> bool {
+		false
+	}
 	fn is_finite(&self) -> bool {
 		true
 	}
@@ -210,6 +368,9 @@ impl GridConstraints for TorusSpace {
 	fn is_repeat_y(&self) -> bool {
 		true
 	}
+	fn is_mirrored_x(&self) -> bool {
+		false
+	}
 	fn is_finite(&self) -> bool {
 		true
 	}
@@ -233,6 +394,9 @@ impl GridConstraints for VerticalCylinderSpace {
 	fn is_repeat_y(&self) -> bool {
 		true
 	}
+	fn is_mirrored_x(&self) -> bool {
+		false
+	}
 	fn is_finite(&self) -> bool {
 		true
 	}
@@ -256,6 +420,60 @@ impl GridConstraints for HorizontalCylinderSpace {
 	fn is_repeat_y(&self) -> bool {
 		false
 	}
+	fn is_mirrored_x(&self) -> bool {
+		false
+	}
+	fn is_finite(&self) -> bool {
+		true
+	}
+}
+
+impl GridConstraints for MobiusSpace {
+	fn can(&self, pos: &Vec2i) -> bool {
+		0 <= pos.y && pos.y < self.size.y
+	}
+	fn remap(&self, pos: &Vec2i) -> Vec2i {
+		debug_assert!(self.can(pos));
+		let (x, mirrored) = rem_repeat_mirrored(pos.x, self.size.x);
+		let y = if mirrored { self.size.y - 1 - pos.y } else { pos.y };
+		Vec2i::new(x, y)
+	}
+
+	fn is_repeat_x(&self) -> bool {
+		true
+	}
+	fn is_repeat_y(&self) -> bool {
+		false
+	}
+	fn is_mirrored_x(&self) -> bool {
+		true
+	}
+	fn is_finite(&self) -> bool {
+		true
+	}
+}
+
+impl GridConstraints for KleinBottleSpace {
+	fn can(&self, _pos: &Vec2i) -> bool {
+		true
+	}
+	fn remap(&self, pos: &Vec2i) -> Vec2i {
+		debug_assert!(self.can(pos));
+		let (x, mirrored) = rem_repeat_mirrored(pos.x, self.size.x);
+		let y = rem_repeat_to_interval(&0, &pos.y, &self.size.y);
+		let y = if mirrored { self.size.y - 1 - y } else { y };
+		Vec2i::new(x, y)
+	}
+
+	fn is_repeat_x(&self) -> bool {
+		true
+	}
+	fn is_repeat_y(&self) -> bool {
+		true
+	}
+	fn is_mirrored_x(&self) -> bool {
+		true
+	}
 	fn is_finite(&self) -> bool {
 		true
 	}
@@ -275,6 +493,9 @@ impl GridConstraints for InfiniteSpace {
 	fn is_repeat_y(&self) -> bool {
 		false
 	}
+	fn is_mirrored_x(&self) -> bool {
+		false
+	}
 	fn is_finite(&self) -> bool {
 		false
 	}
@@ -329,15 +550,9 @@ impl<'a, T: Clone> Iterator for VecGridIterator<'a, T> {
 	type Item = (Vec2i, &'a T);
 	fn next(&mut self) -> Option<Self::Item> {
 		loop {
-			match self.iter.next()? {
-				Some(elem) => {
-					let result_pos = self.pos.clone();
-					self.pos = next_in_rect(&self.pos, &self.size)?;
-					return Some((result_pos, &elem))
-				},
-				None => {
-					self.pos = next_in_rect(&self.pos, &self.size)?;
-				},
+			if let (index, Some(elem)) = self.iter.next()? {
+				let index = index as i32;
+				return Some((Vec2i::new(index % self.width, index / self.width), elem));
 			}
 		}
 	}
@@ -351,9 +566,8 @@ impl<'b, T: 'b, C> MyIter<'b, T> for VecGrid<T, C> where
 
 	fn iter(&'b self) -> Self::Iter {
 		VecGridIterator { 
-			iter: self.grid.iter(), 
-			pos: Vec2i::default(),
-			size: self.constraints.get_size(),
+			iter: self.grid.iter().enumerate(), 
+			width: self.constraints.get_size().x,
 		}
 	}
 }
@@ -384,8 +598,7 @@ impl<T: 'static, C> Grid<T> for VecGrid<T, C> where
 	fn get_owned(&mut self, pos: &Vec2i) -> Option<T> {
 		debug_assert!(self.can(&pos));
 		let pos = self.to_pos(pos);
-		self.grid.push(None);
-		let owned = self.grid.swap_remove(pos);
+		let owned = self.grid[pos].take();
 		if owned.is_some() {
 			self.count -= 1;
 		}
@@ -423,6 +636,11 @@ impl<T: 'static, C> Grid<T> for VecGrid<T, C> where
 		for t in self.grid.iter_mut() {
 			*t = None;
 		}
+		self.count = 0;
+	}
+
+	fn remap(&self, pos: &Vec2i) -> Vec2i {
+		self.constraints.remap(pos)
 	}
 
 	fn is_repeat_x(&self) -> bool {
@@ -431,6 +649,9 @@ impl<T: 'static, C> Grid<T> for VecGrid<T, C> where
 	fn is_repeat_y(&self) -> bool {
 		self.constraints.is_repeat_y()
 	}
+	fn is_mirrored_x(&self) -> bool {
+		self.constraints.is_mirrored_x()
+	}
 	fn is_finite(&self) -> bool {
 		self.constraints.is_finite()
 	}
@@ -498,7 +719,7 @@ impl<T: 'static, C> Grid<T> for HashMapGrid<T, C> where
 		}
 	}
 	fn set_unchecked(&mut self, pos: &Vec2i, obj: T) {
-		debug_assert!(self.can(&pos) && !self.has(&pos));
+		debug_assert!(self.can(&pos));
 		let pos = self.constraints.remap(pos);
 		self.grid.insert(pos, obj);
 	}
@@ -511,12 +732,222 @@ impl<T: 'static, C> Grid<T> for HashMapGrid<T, C> where
 		self.grid.clear();
 	}
 
+	fn remap(&self, pos: &Vec2i) -> Vec2i {
+		self.constraints.remap(pos)
+	}
+
+	fn is_repeat_x(&self) -> bool {
+		self.constraints.is_repeat_x()
+	}
+	fn is_repeat_y(&self) -> bool {
+		self.constraints.is_repeat_y()
+	}
+	fn is_mirrored_x(&self) -> bool {
+		self.constraints.is_mirrored_x()
+	}
+	fn is_finite(&self) -> bool {
+		self.constraints.is_finite()
+	}
+}
+
+impl ChunkHasher {
+	fn add(&mut self, value: u64) {
+		self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+	}
+}
+
+impl Hasher for ChunkHasher {
+	fn finish(&self) -> u64 {
+		self.0
+	}
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.add(u64::from(*byte));
+		}
+	}
+	fn write_i32(&mut self, value: i32) {
+		self.add(u64::from(value as u32));
+	}
+}
+
+impl<T> Chunk<T> {
+	fn new() -> Self {
+		let mut cells = Vec::with_capacity((CHUNK_SIZE * CHUNK_SIZE) as usize);
+		cells.resize_with((CHUNK_SIZE * CHUNK_SIZE) as usize, || None);
+		Chunk { cells, count: 0 }
+	}
+}
+
+fn chunk_pos(pos: &Vec2i) -> (Vec2i, usize) {
+	let chunk = Vec2i::new(pos.x.div_euclid(CHUNK_SIZE), pos.y.div_euclid(CHUNK_SIZE));
+	let inner = pos.x.rem_euclid(CHUNK_SIZE) + pos.y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE;
+	(chunk, inner as usize)
+}
+
+impl<T, C> ChunkGrid<T, C> where
+	C: CanFitInSize
+{
+	pub fn new(size: &Vec2i) -> Self {
+		ChunkGrid {
+			chunks: ChunkMap::default(),
+			constraints: C::new(size),
+			count: 0,
+			empty_chunks: 0,
+		}
+	}
+}
+
+impl<T, C> ChunkGrid<T, C> where
+	C: Default
+{
+	pub fn new_infinite() -> Self {
+		ChunkGrid {
+			chunks: ChunkMap::default(),
+			constraints: C::default(),
+			count: 0,
+			empty_chunks: 0,
+		}
+	}
+}
+
+impl<T, C> ChunkGrid<T, C> where
+	C: GridConstraints
+{
+	fn cell(&self, pos: &Vec2i) -> Option<&Option<T>> {
+		let (chunk, inner) = chunk_pos(&self.constraints.remap(pos));
+		self.chunks.get(&chunk).map(|chunk| &chunk.cells[inner])
+	}
+
+	fn free_empty_chunks(&mut self) {
+		if self.empty_chunks > KEPT_EMPTY_CHUNKS && self.empty_chunks * 2 > self.chunks.len() {
+			self.chunks.retain(|_, chunk| chunk.count != 0);
+			self.empty_chunks = 0;
+		}
+	}
+}
+
+impl<'a, T> Iterator for ChunkGridIterator<'a, T> {
+	type Item = (Vec2i, &'a T);
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some((chunk, cells)) = &mut self.current {
+				for (inner, cell) in cells {
+					if let Some(elem) = cell {
+						let inner = inner as i32;
+						let pos = Vec2i::new(
+							chunk.x * CHUNK_SIZE + inner % CHUNK_SIZE,
+							chunk.y * CHUNK_SIZE + inner / CHUNK_SIZE,
+						);
+						return Some((pos, elem));
+					}
+				}
+			}
+			let (chunk, cells) = self.chunks.next()?;
+			self.current = Some((chunk.clone(), cells.cells.iter().enumerate()));
+		}
+	}
+}
+
+impl<'b, T: 'b, C> MyIter<'b, T> for ChunkGrid<T, C> where
+	C: GridConstraints
+{
+	type Iter = ChunkGridIterator<'b, T>;
+
+	fn iter(&'b self) -> Self::Iter {
+		ChunkGridIterator {
+			chunks: self.chunks.iter(),
+			current: None,
+		}
+	}
+}
+
+impl<T: 'static, C> Grid<T> for ChunkGrid<T, C> where
+	C: GridConstraints
+{
+	fn can(&self, pos: &Vec2i) -> bool {
+		self.constraints.can(pos)
+	}
+	fn has(&self, pos: &Vec2i) -> bool {
+		debug_assert!(self.can(pos));
+		self.get(pos).is_some()
+	}
+
+	fn get<'a>(&'a self, pos: &Vec2i) -> Option<&'a T> {
+		debug_assert!(self.can(pos));
+		self.cell(pos)?.as_ref()
+	}
+	fn get_mut<'a>(&'a mut self, pos: &Vec2i) -> Option<&'a mut T> {
+		debug_assert!(self.can(pos));
+		let (chunk, inner) = chunk_pos(&self.constraints.remap(pos));
+		self.chunks.get_mut(&chunk)?.cells[inner].as_mut()
+	}
+	fn get_owned(&mut self, pos: &Vec2i) -> Option<T> {
+		debug_assert!(self.can(pos));
+		let (chunk_pos, inner) = chunk_pos(&self.constraints.remap(pos));
+		let chunk = self.chunks.get_mut(&chunk_pos)?;
+		let owned = chunk.cells[inner].take()?;
+		chunk.count -= 1;
+		self.count -= 1;
+		if chunk.count == 0 {
+			self.empty_chunks += 1;
+			self.free_empty_chunks();
+		}
+		Some(owned)
+	}
+
+	fn set(&mut self, pos: &Vec2i, obj: T) -> Option<T> {
+		if self.can(pos) && !self.has(pos) {
+			self.set_unchecked(pos, obj);
+			None
+		} else {
+			Some(obj)
+		}
+	}
+	fn set_unchecked(&mut self, pos: &Vec2i, obj: T) {
+		debug_assert!(self.can(pos));
+		let (chunk, inner) = chunk_pos(&self.constraints.remap(pos));
+		let chunk = match self.chunks.entry(chunk) {
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => {
+				self.empty_chunks += 1;
+				entry.insert(Chunk::new())
+			},
+		};
+		let elem = &mut chunk.cells[inner];
+
+		if elem.is_none() {
+			if chunk.count == 0 {
+				self.empty_chunks -= 1;
+			}
+			chunk.count += 1;
+			self.count += 1;
+		}
+		*elem = Some(obj);
+	}
+
+	fn len(&self) -> usize {
+		self.count
+	}
+
+	fn clear(&mut self) {
+		self.chunks.clear();
+		self.count = 0;
+		self.empty_chunks = 0;
+	}
+
+	fn remap(&self, pos: &Vec2i) -> Vec2i {
+		self.constraints.remap(pos)
+	}
+
 	fn is_repeat_x(&self) -> bool {
 		self.constraints.is_repeat_x()
 	}
 	fn is_repeat_y(&self) -> bool {
 		self.constraints.is_repeat_y()
 	}
+	fn is_mirrored_x(&self) -> bool {
+		self.constraints.is_mirrored_x()
+	}
 	fn is_finite(&self) -> bool {
 		self.constraints.is_finite()
 	}
@@ -0,0 +1,51 @@
+use crate::*;
+
+/// Keeps position of the selected bot: follows it when it moves and forgets it when it is destroyed. Observer events always name the cell where the bot was last seen, so the position identifies the bot.
+#[derive(Default)]
+pub struct BotTracker {
+	pub pos: Option<Vec2i>,
+}
+
+impl<C> Observer<C> for BotTracker {
+	fn movement(&mut self, from: &Vec2i, to: &Vec2i, _bot: &C) {
+		if self.pos.as_ref() == Some(from) {
+			self.pos = Some(to.clone());
+		}
+	}
+
+	fn destruction(&mut self, pos: &Vec2i, _bot: &C) {
+		if self.pos.as_ref() == Some(pos) {
+			self.pos = None;
+		}
+	}
+}
+
+/// Full state of the bot with disassembled program, and index of the line with the current instruction
+pub fn inspect_text(pos: &Vec2i, bot: &Bot) -> (String, usize) {
+	let mut text = format!(
+		"\
+		bot at ({}, {})\n\
+		alive: {}\n\
+		timer: {}\n\
+		protein: {}\n\
+		color: #{:02x}{:02x}{:02x}\n\
+		lineage: {}\n\
+		last comand: {}\n\
+		eip: {}\n\
+		\n",
+		pos.x, pos.y,
+		bot.alive,
+		bot.timer,
+		bot.protein,
+		bot.color.r, bot.color.g, bot.color.b,
+		bot.lineage,
+		bot.last_comand.map(|comand| format!("{:?}", comand)).unwrap_or_else(|| "none".to_string()),
+		bot.eip.0,
+	);
+	let header_lines = text.lines().count();
+	for (i, comand) in bot.program.iter().enumerate() {
+		let marker = if i == bot.eip.0 { '>' } else { ' ' };
+		text += &format!("{}{:2}: {}\n", marker, i, comand);
+	}
+	(text, header_lines + bot.eip.0)
+}
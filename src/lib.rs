@@ -3,34 +3,114 @@ pub use rand::seq::SliceRandom;
 pub use rand::Rng;
 pub use rand_pcg::Pcg32;
 
+#[cfg(feature = "gui")]
 pub use ambassador::Delegate;
 
+#[cfg(feature = "cli")]
 pub use clap::clap_app;
 
+#[cfg(feature = "gui")]
 pub use gesture_recognizer::*;
 
+#[cfg(feature = "gui")]
 pub use bufdraw::*;
-pub use bufdraw::image::*;
+#[cfg(feature = "gui")]
+pub use bufdraw::image::{Image, ImageTrait};
+#[cfg(feature = "gui")]
 pub use bufdraw::measure::*;
-pub use bufdraw::text::*;
-pub use bufdraw::vec::Vec2i;
-pub use bufdraw::image::Color;
-pub use bufdraw::interpolate::Interpolate;
+#[cfg(feature = "gui")]
+pub use bufdraw::text::{Font, TextCache, preprocess_text};
+
+pub mod vector;
+pub use crate::vector::Vec2i;
+pub mod color;
+pub use crate::color::*;
 
 pub mod gridtools;
 pub use gridtools::*;
+#[cfg(feature = "gui")]
+pub mod screen;
+#[cfg(feature = "gui")]
+pub use crate::screen::*;
+#[cfg(feature = "gui")]
 pub mod text_window;
+#[cfg(feature = "gui")]
 pub use text_window::*;
+#[cfg(feature = "gui")]
 pub mod camera;
+#[cfg(feature = "gui")]
 pub use crate::camera::*;
+#[cfg(feature = "gui")]
 pub mod rescaled_window;
+#[cfg(feature = "gui")]
 pub use crate::rescaled_window::*;
-
-#[derive(Clone, Debug)]
+#[cfg(feature = "gui")]
+pub mod window;
+#[cfg(feature = "gui")]
+pub use crate::window::*;
+#[cfg(feature = "gui")]
+pub mod view_mode;
+#[cfg(feature = "gui")]
+pub use crate::view_mode::*;
+#[cfg(feature = "gui")]
+pub mod inspect;
+#[cfg(feature = "gui")]
+pub use crate::inspect::*;
+#[cfg(feature = "gui")]
+pub mod tools;
+#[cfg(feature = "gui")]
+pub use crate::tools::*;
+#[cfg(feature = "gui")]
+pub mod charts;
+#[cfg(feature = "gui")]
+pub use crate::charts::*;
+#[cfg(feature = "gui")]
+pub mod minimap;
+#[cfg(feature = "gui")]
+pub use crate::minimap::*;
+#[cfg(feature = "gui")]
+pub mod follow;
+#[cfg(feature = "gui")]
+pub use crate::follow::*;
+#[cfg(feature = "gui")]
+pub mod render;
+#[cfg(feature = "gui")]
+pub use crate::render::*;
+pub mod config;
+pub use crate::config::*;
+// Time can be measured on the web only through the window
+#[cfg(any(not(target_arch = "wasm32"), feature = "gui"))]
+pub mod benchmark;
+#[cfg(any(not(target_arch = "wasm32"), feature = "gui"))]
+pub use crate::benchmark::*;
+pub mod ruleset;
+pub use crate::ruleset::*;
+pub mod genome;
+pub use crate::genome::*;
+
+/// Smallest scale, when one pixel shows 64×64 cells
+pub const MIN_SCALE: f32 = 1.0 / 64.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 /// Integer from 0 to PROGRAM_SIZE
 pub struct ProgramPos(usize);
 
-#[derive(Clone, Debug)]
+impl ProgramPos {
+	/// `None` if `pos` is not less than `PROGRAM_SIZE`
+	pub fn new(pos: usize) -> Option<Self> {
+		if pos < PROGRAM_SIZE {
+			Some(ProgramPos(pos))
+		} else {
+			None
+		}
+	}
+
+	pub fn get(self) -> usize {
+		self.0
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, enum_utils::FromStr, enum_utils::IterVariants, Debug)]
 pub enum Comands {
 	Multiply,
 	Photosynthesis,
@@ -39,14 +119,14 @@ pub enum Comands {
 	Move,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Comand {
 	pub comand: Comands,
 	pub goto_success: ProgramPos,
 	pub goto_fail: ProgramPos,
 }
 
-pub type Program = Vec<Comand>;
+pub type Program = [Comand; PROGRAM_SIZE];
 
 #[derive(Clone, Debug)]
 pub struct Bot {
@@ -58,13 +138,105 @@ pub struct Bot {
 
 	pub program: Program,
 	pub eip: ProgramPos,
+	pub last_comand: Option<Comands>,
+	/// Id of the random bot this bot descended from
+	pub lineage: u32,
 }
 
-pub trait Creature {
+/// Value which is made randomly and changed by mutations: creatures and parts of their genome.
+pub trait Gene: Sized {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self;
 	fn mutate<R: Rng + ?Sized>(&mut self, rng: &mut R);
 }
 
+/// Agent living in the `World`. Runner and renderer work with any creature, `Bot` is the original one.
+pub trait Creature: Gene {
+	/// Rules of the step, common for all creatures of the world, so they can be chosen at runtime
+	type Rules: ?Sized;
+
+	/// Prepares randomly made creature to be placed into the world
+	fn init(&mut self, constants: &Constants, lineage: u32);
+	/// Step of the creature at `pos`, returns its new position and state, or `None` if it is destroyed. Move is reported to the observer by the runner when it is applied
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(rules: &Self::Rules, constants: &Constants, rng: &mut R, world: &mut World<G>, observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)>;
+	/// Returns resources of the creature to the world when it is removed
+	fn decompose(&mut self, resources: &mut Resources);
+	/// Natural color of the creature, the window draws it with this color
+	fn color(&self) -> Color;
+}
+
+/// Receives events which happen during the simulation step. All methods do nothing by default, so implement only needed ones.
+#[allow(unused_variables)]
+pub trait Observer<C = Bot> {
+	fn birth(&mut self, parent_pos: &Vec2i, parent: &C, child_pos: &Vec2i, child: &C) {}
+	/// Creature became dead, but still exists
+	fn death(&mut self, pos: &Vec2i, bot: &C) {}
+	/// Creature is removed from the world, its resources are returned to the world
+	fn destruction(&mut self, pos: &Vec2i, bot: &C) {}
+	fn attack(&mut self, attacker_pos: &Vec2i, attacker: &C, victim_pos: &Vec2i, victim: &C, amount: u32) {}
+	/// Reported only for moves which really happened
+	fn movement(&mut self, from: &Vec2i, to: &Vec2i, bot: &C) {}
+	fn photosynthesis(&mut self, pos: &Vec2i, bot: &C) {}
+	fn food(&mut self, pos: &Vec2i, bot: &C) {}
+}
+
+impl<C> Observer<C> for () {}
+
+impl<C, O: Observer<C> + ?Sized> Observer<C> for &mut O {
+	fn birth(&mut self, parent_pos: &Vec2i, parent: &C, child_pos: &Vec2i, child: &C) {
+		(**self).birth(parent_pos, parent, child_pos, child);
+	}
+	fn death(&mut self, pos: &Vec2i, bot: &C) {
+		(**self).death(pos, bot);
+	}
+	fn destruction(&mut self, pos: &Vec2i, bot: &C) {
+		(**self).destruction(pos, bot);
+	}
+	fn attack(&mut self, attacker_pos: &Vec2i, attacker: &C, victim_pos: &Vec2i, victim: &C, amount: u32) {
+		(**self).attack(attacker_pos, attacker, victim_pos, victim, amount);
+	}
+	fn movement(&mut self, from: &Vec2i, to: &Vec2i, bot: &C) {
+		(**self).movement(from, to, bot);
+	}
+	fn photosynthesis(&mut self, pos: &Vec2i, bot: &C) {
+		(**self).photosynthesis(pos, bot);
+	}
+	fn food(&mut self, pos: &Vec2i, bot: &C) {
+		(**self).food(pos, bot);
+	}
+}
+
+/// Sends each event to both observers
+impl<C, A: Observer<C>, B: Observer<C>> Observer<C> for (A, B) {
+	fn birth(&mut self, parent_pos: &Vec2i, parent: &C, child_pos: &Vec2i, child: &C) {
+		self.0.birth(parent_pos, parent, child_pos, child);
+		self.1.birth(parent_pos, parent, child_pos, child);
+	}
+	fn death(&mut self, pos: &Vec2i, bot: &C) {
+		self.0.death(pos, bot);
+		self.1.death(pos, bot);
+	}
+	fn destruction(&mut self, pos: &Vec2i, bot: &C) {
+		self.0.destruction(pos, bot);
+		self.1.destruction(pos, bot);
+	}
+	fn attack(&mut self, attacker_pos: &Vec2i, attacker: &C, victim_pos: &Vec2i, victim: &C, amount: u32) {
+		self.0.attack(attacker_pos, attacker, victim_pos, victim, amount);
+		self.1.attack(attacker_pos, attacker, victim_pos, victim, amount);
+	}
+	fn movement(&mut self, from: &Vec2i, to: &Vec2i, bot: &C) {
+		self.0.movement(from, to, bot);
+		self.1.movement(from, to, bot);
+	}
+	fn photosynthesis(&mut self, pos: &Vec2i, bot: &C) {
+		self.0.photosynthesis(pos, bot);
+		self.1.photosynthesis(pos, bot);
+	}
+	fn food(&mut self, pos: &Vec2i, bot: &C) {
+		self.0.food(pos, bot);
+		self.1.food(pos, bot);
+	}
+}
+
 pub struct Resources {
 	pub free_protein: u32,
 	pub oxygen: u32,
@@ -75,6 +247,8 @@ pub struct World<G> {
 	pub size: Vec2i,
 	pub resources: Resources,
 	pub bots: G,
+	/// Lineage id for the next random bot
+	pub next_lineage: u32,
 }
 
 pub trait Stole {
@@ -83,28 +257,46 @@ pub trait Stole {
 	fn stole_full(&mut self, other: &mut Self);
 }
 
-pub struct PerformanceInfo {
-	pub tps: usize,
-	pub steps_per_frame: usize,
-	pub fps: usize,
-}
-
-#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug)]
+#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug, serde::Serialize)]
 pub enum FieldTopology {
 	Rect,
 	Torus,
 	VerticalCylinder,
 	HorizontalCylinder,
+	Mobius,
+	KleinBottle,
 	Infinite,
 }
 
-#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug)]
+#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug, serde::Serialize)]
 pub enum FieldContainer {
 	HashMap,
 	Vec,
+	Chunked,
+}
+
+/// Hexagonal lattice needs even height on topologies repeated by y and odd height on mirrored ones, otherwise parity of rows breaks on the border.
+#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug, serde::Serialize)]
+pub enum FieldNeighborhood {
+	Moore,
+	VonNeumann,
+	Hexagonal,
+}
+
+/// Order of turns of bots during a tick. With any order each bot makes at most one turn per tick, bots born during the tick make no turns.
+#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug, serde::Serialize)]
+pub enum UpdateOrder {
+	/// Sorted by coordinates, starting from the top left corner
+	Sorted,
+	/// Random order, shuffled each tick
+	Shuffled,
+	/// Bots make turns in random order and stay in their cells, newborns appear only when all of them made a turn. Then moves and births are applied at once: when several bots claim the same cell, random one of them takes it, other bots stay and other newborns are not born.
+	///
+	/// Only cells are claimed simultaneously. Attacks, photosynthesis and food change the world during the turn, so shared resources go to bots in the random order.
+	SimultaneousMoves,
 }
 
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize)]
 pub struct Constants {
 	pub width: i32,
 	pub height: i32,
@@ -125,40 +317,14 @@ pub struct Constants {
 
 	pub topology: FieldTopology,
 	pub container: FieldContainer,
-}
-
-#[derive(Delegate)]
-#[delegate(ImageTrait, target = "window")]
-pub struct Window<R, G> {
-	pub window: WindowBase<R, G>,
-	pub gesture_recognizer: GestureRecognizer,
-}
-
-#[derive(Delegate)]
-#[delegate(ImageTrait, target = "image")]
-pub struct WindowBase<R, G> {
-	pub image: Image,
-	pub bot_image: Image,
-
-	pub world: World<G>,
-	pub rng: R,
-	pub cam: FloatImageCamera,
-
-	pub draw: FpsWithCounter,
-	pub simulate: FpsWithCounter,
-
-	pub last_mouse_pos: Vec2i,
-	pub mouse_move: bool,
-	pub current_cam_scale: f32,
-
-	pub text_cache: TextCache,
-
-	pub performance_info: PerformanceInfo,
-
-	pub fps: FpsByLastTime,
-	pub tps: FpsByLastTime,
-
-	pub constants: Constants,
+	pub neighborhood: FieldNeighborhood,
+	/// Radius in which bot can find another bot to attack
+	pub sense: u32,
+	pub update: UpdateOrder,
+	pub rules: RulesetVariant,
+	/// File with genomes of the initial population, see `read_genomes`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub genomes: Option<String>,
 }
 
 pub mod colors {
@@ -213,12 +379,12 @@ pub const PROGRAM_SIZE: usize = 5;
 //----------------------------------------------------------------------------
 //----------------------------------------------------------------------------
 
-impl Creature for Color {
+impl Gene for Color {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
 		Color {
-			r: Creature::make_random(rng),
-			g: Creature::make_random(rng),
-			b: Creature::make_random(rng),
+			r: Gene::make_random(rng),
+			g: Gene::make_random(rng),
+			b: Gene::make_random(rng),
 			a: 255,
 		}
 	}
@@ -233,7 +399,7 @@ impl Creature for Color {
 	}
 }
 
-impl Creature for u8 {
+impl Gene for u8 {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
 		rng.gen::<u8>()
 	}
@@ -243,7 +409,7 @@ impl Creature for u8 {
 	}
 }
 
-impl Creature for Comands {
+impl Gene for Comands {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
 		use Comands::*;
 		let value = rng.gen_range(0, 5);
@@ -272,7 +438,7 @@ impl Creature for Comands {
 	}
 }
 
-impl Creature for ProgramPos {
+impl Gene for ProgramPos {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
 		ProgramPos(rng.gen_range(0, PROGRAM_SIZE))
 	}
@@ -282,7 +448,7 @@ impl Creature for ProgramPos {
 	}
 }
 
-impl Creature for Comand {
+impl Gene for Comand {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
 		Comand {
 			comand: Comands::make_random(rng),
@@ -301,11 +467,15 @@ impl Creature for Comand {
 	}
 }
 
-impl Creature for Program {
+impl Gene for Program {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
-		let mut result = Vec::with_capacity(PROGRAM_SIZE);
-		for _ in 0..PROGRAM_SIZE {
-			result.push(Comand::make_random(rng));
+		let mut result = [Comand {
+			comand: Comands::Multiply,
+			goto_success: ProgramPos(0),
+			goto_fail: ProgramPos(0),
+		}; PROGRAM_SIZE];
+		for comand in result.iter_mut() {
+			*comand = Comand::make_random(rng);
 		}
 		result
 	}
@@ -317,15 +487,17 @@ impl Creature for Program {
 	}
 }
 
-impl Creature for Bot {
+impl Gene for Bot {
 	fn make_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
 		Bot {
-			color: Creature::make_random(rng),
+			color: Gene::make_random(rng),
 			timer: 0,
 			protein: 0,
 			program: Program::make_random(rng),
 			eip: ProgramPos(0),
-			alive: true
+			alive: true,
+			last_comand: None,
+			lineage: 0,
 		}
 	}
 
@@ -335,6 +507,28 @@ impl Creature for Bot {
 	}
 }
 
+impl Creature for Bot {
+	type Rules = dyn Ruleset;
+
+	fn init(&mut self, constants: &Constants, lineage: u32) {
+		self.timer = constants.live;
+		self.protein = 0;
+		self.lineage = lineage;
+	}
+
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(rules: &dyn Ruleset, constants: &Constants, rng: &mut R, world: &mut World<G>, observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)> {
+		process(rules, constants, rng, world, observer, pos)
+	}
+
+	fn decompose(&mut self, resources: &mut Resources) {
+		resources.free_protein.stole_full(&mut self.protein);
+	}
+
+	fn color(&self) -> Color {
+		self.color.clone()
+	}
+}
+
 pub fn normalize_coords(mut pos: Vec2i, size: &Vec2i) -> Vec2i {
 	pos.x = pos.x.abs();
 	pos.y = pos.y.abs();
@@ -343,36 +537,133 @@ pub fn normalize_coords(mut pos: Vec2i, size: &Vec2i) -> Vec2i {
 	pos
 }
 
-pub fn insert_random_bot<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, mut rng: &mut R, world: &mut World<G>) -> bool {
-	let mut bot = Bot::make_random(&mut rng);
+pub fn insert_random_bot<C: Creature, R: Rng + ?Sized, G: Grid<C>>(constants: &Constants, mut rng: &mut R, world: &mut World<G>) -> bool {
+	let mut bot = C::make_random(&mut rng);
 	let mut bot_pos = Vec2i {
 		x: rng.gen(),
 		y: rng.gen(),
 	};
-	bot.timer = constants.live;
-	bot.protein = 0;
+	bot.init(constants, world.next_lineage);
+	world.next_lineage += 1;
 	bot_pos = normalize_coords(bot_pos, &world.size);
-	if let Some(mut bot) = world.bots.set(&bot_pos, bot) {
-		world.resources.free_protein.stole_full(&mut bot.protein);
-		false
-	} else {
+	if world.bots.can(&bot_pos) && !world.bots.has(&bot_pos) {
+		world.bots.set_unchecked(&bot_pos, bot);
 		true
+	} else {
+		bot.decompose(&mut world.resources);
+		false
 	}
 } 
 
-pub fn process_world<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, mut rng: &mut R, world: &mut World<G>) {
+pub fn process_world<C: Creature, R: Rng + ?Sized, G: Grid<C>, O: Observer<C> + ?Sized>(rules: &C::Rules, constants: &Constants, mut rng: &mut R, world: &mut World<G>, observer: &mut O) {
 	let mut positions: Vec<Vec2i> = world.bots.iter().map(|x| x.0).collect();
+	// Order of iteration of some containers differs from run to run, so it's sorted even before shuffling to keep the seed meaningful
 	positions.sort();
+	match constants.update {
+		UpdateOrder::Sorted => {},
+		UpdateOrder::Shuffled => positions.shuffle(rng),
+		UpdateOrder::SimultaneousMoves => return process_simultaneous_moves(rules, constants, rng, world, observer, positions),
+	}
+
+	let mut taken = TakenTurns::default();
 	for pos in positions {
-		let result = process(&constants, &mut rng, &mut world.resources, &mut world.bots, pos);
+		// Bot moved here or was born here during this tick
+		if taken.0.contains(&pos) {
+			continue;
+		}
+		let result = C::step(rules, &constants, &mut rng, world, &mut (&mut *observer, &mut taken), pos.clone());
 		if let Some((new_pos, new_bot)) = result {
-			if let Some(mut new_bot) = world.bots.set(&new_pos, new_bot) {
-				world.resources.free_protein.stole_full(&mut new_bot.protein);
+			let new_pos = world.bots.remap(&new_pos);
+			// Failed move destroys the mover, so the occupant of the cell still makes its turn
+			if place_bot(world, observer, &pos, &new_pos, new_bot) && new_pos != pos {
+				taken.0.insert(new_pos);
 			}
 		}
 	}
 }
 
+/// Cell which the creature wants to take when all creatures made a turn
+enum Claim<C> {
+	/// Creature from this cell moves
+	Move(Vec2i),
+	Birth(C),
+}
+
+/// Positions of creatures born during one step
+#[derive(Default)]
+struct Births(Vec<Vec2i>);
+
+impl<C> Observer<C> for Births {
+	fn birth(&mut self, _parent_pos: &Vec2i, _parent: &C, child_pos: &Vec2i, _child: &C) {
+		self.0.push(child_pos.clone());
+	}
+}
+
+/// Creatures stay in their cells and newborns are kept aside until all creatures made a turn, then cells are claimed at once
+fn process_simultaneous_moves<C: Creature, R: Rng + ?Sized, G: Grid<C>, O: Observer<C> + ?Sized>(rules: &C::Rules, constants: &Constants, rng: &mut R, world: &mut World<G>, observer: &mut O, mut positions: Vec<Vec2i>) {
+	// Random order, so bots with lower coordinates don't get shared resources first
+	positions.shuffle(rng);
+	let mut claims = Vec::new();
+	for pos in positions {
+		let mut births = Births::default();
+		let result = C::step(rules, constants, rng, world, &mut (&mut *observer, &mut births), pos.clone());
+		for child_pos in births.0 {
+			if let Some(child) = world.bots.get_owned(&child_pos) {
+				claims.push((child_pos, Claim::Birth(child)));
+			}
+		}
+		if let Some((new_pos, new_bot)) = result {
+			let new_pos = world.bots.remap(&new_pos);
+			if place_bot(world, observer, &pos, &pos, new_bot) && new_pos != pos {
+				claims.push((new_pos, Claim::Move(pos)));
+			}
+		}
+	}
+
+	// When several creatures claim the same cell, random one of them takes it
+	claims.shuffle(rng);
+	for (to, claim) in claims {
+		match claim {
+			Claim::Move(from) => if !world.bots.has(&to) {
+				if let Some(bot) = world.bots.get_owned(&from) {
+					place_bot(world, observer, &from, &to, bot);
+				}
+			},
+			Claim::Birth(mut child) => if world.bots.has(&to) {
+				observer.destruction(&to, &child);
+				child.decompose(&mut world.resources);
+			} else {
+				world.bots.set_unchecked(&to, child);
+			},
+		}
+	}
+}
+
+/// Puts the bot which made a turn at `from` into `to` and reports the move. Bot is destroyed if `to` is already occupied, returns `false` then. Destruction is reported at `from`, because observers haven't seen the bot anywhere else.
+fn place_bot<C: Creature, G: Grid<C>, O: Observer<C> + ?Sized>(world: &mut World<G>, observer: &mut O, from: &Vec2i, to: &Vec2i, mut bot: C) -> bool {
+	if !world.bots.can(to) || world.bots.has(to) {
+		observer.destruction(from, &bot);
+		bot.decompose(&mut world.resources);
+		false
+	} else {
+		if from != to {
+			observer.movement(from, to, &bot);
+		}
+		world.bots.set_unchecked(to, bot);
+		true
+	}
+}
+
+/// Positions of bots which can't make a turn in this tick anymore
+#[derive(Default)]
+struct TakenTurns(std::collections::HashSet<Vec2i>);
+
+impl<C> Observer<C> for TakenTurns {
+	fn birth(&mut self, _parent_pos: &Vec2i, _parent: &C, child_pos: &Vec2i, _child: &C) {
+		self.0.insert(child_pos.clone());
+	}
+}
+
 impl Stole for u32 {
 	fn can_stole(self) -> bool {
 		self > 0
@@ -399,52 +690,42 @@ impl Drop for Bot {
 	}
 }
 
-pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R, resources: &mut Resources, bots: &mut G, pos: Vec2i) -> Option<(Vec2i, Bot)> {
+/// Step of the bot by the rules
+pub fn process<S: Ruleset + ?Sized, R: Rng + ?Sized, G: Grid<Bot>, O: Observer + ?Sized>(ruleset: &S, constants: &Constants, rng: &mut R, world: &mut World<G>, observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Bot)> {
+	let resources = &mut world.resources;
+	let bots = &mut world.bots;
 	let mut bot = bots.get_owned(&pos)?;
 
-	bot.timer = bot.timer.saturating_sub(1);
+	ruleset.age(constants, &mut bot);
 
 	// Момент смерти
 	if bot.alive && bot.timer == 0 {
 		bot.color = bot.color.interpolate(&colors::BLACK, 0.5);
 		bot.alive = false;
 		bot.timer = constants.die;
+		observer.death(&pos, &bot);
 	}
 
 	// Полное уничтожение
 	if !bot.alive && bot.timer == 0 {
+		observer.destruction(&pos, &bot);
 		return destruct(resources, &mut bot);
 	}
 
 	if bot.alive {	
-		let available_cells: Vec<Vec2i> = available_cells(bots, &pos);
-
-		let void_around: Vec<Vec2i> = available_cells.iter().filter(|&pos| 
-			bots.can(pos) && !bots.has(pos)
-		).cloned().collect();
-
-		let alive_around: Vec<Vec2i> = available_cells.iter().filter(|&pos| 
-			if bots.can(&pos) {
-				if let Some(around) = bots.get(pos) { 
-					around.alive
-				} else { 
-					false 
-				}
-			} else {
-				false
-			}
-		).cloned().collect();
+		let offsets = constants.neighborhood.offsets(&pos);
+		let void_around = bots.void_around(&pos, offsets);
+		let alive_around = bots.filled_around(&pos, offsets, |around| around.alive);
 
 		// Действия при жизни
 		for _ in 0..constants.comand {
 			// Бот размножается, если слишком много протеина, и если может
 			if bot.protein >= 10 * constants.multiply {
+				bot.last_comand = Some(Comands::Multiply);
 				let result = multiply(&constants, rng, &mut bot, &void_around);
 				if let Some((new_pos, new_bot)) = result {
 					bot.color = bot.color.interpolate(&colors::BLUE, 0.03);
-					if let Some(mut new_bot) = bots.set(&new_pos, new_bot) {
-						resources.free_protein.stole_full(&mut new_bot.protein);
-					}
+					place_child(resources, bots, observer, &pos, &bot, new_pos, new_bot);
 				}
 				bot.color = bot.color.interpolate(&colors::BLUE, 0.03);
 				return Some((pos, bot));
@@ -452,7 +733,8 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 
 			use Comands::*;
 
-			let comand = bot.program[bot.eip.0].clone();
+			let comand = bot.program[bot.eip.0];
+			bot.last_comand = Some(comand.comand);
 			match comand.comand {
 				Multiply => {
 					if bot.protein >= constants.multiply {
@@ -461,9 +743,7 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 							new_bot.eip = ProgramPos(0);
 							bot.eip = comand.goto_success;
 							bot.color = bot.color.interpolate(&colors::BLUE, 0.03);
-							if let Some(mut new_bot) = bots.set(&new_pos, new_bot) {
-								resources.free_protein.stole_full(&mut new_bot.protein);
-							}
+							place_child(resources, bots, observer, &pos, &bot, new_pos, new_bot);
 							return Some((pos, bot));
 						} else {
 							bot.eip = comand.goto_fail;
@@ -473,25 +753,25 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 					}
 				},
 				Photosynthesis => {
-					if resources.free_protein.can_stole() && resources.carbon.can_stole() {
-						bot.protein.stole(&mut resources.free_protein);
-						resources.oxygen.stole(&mut resources.carbon);
-
+					if ruleset.photosynthesis(resources, &mut bot) {
 						bot.color = bot.color.interpolate(&colors::GREEN, 0.03);
 						bot.eip = comand.goto_success;
+						observer.photosynthesis(&pos, &bot);
 						return Some((pos, bot));
 					} else {
 						bot.eip = comand.goto_fail;
 					}
 				},
 				Attack => {
-					if !alive_around.is_empty() && resources.oxygen.can_stole() {
-						let attack_to = alive_around.choose(rng).unwrap();
-
-						if let Some(mut attacked) = bots.get_owned(attack_to) {
-							if attacked.protein.can_stole() {
-								bot.protein.stole(&mut attacked.protein);
-								resources.carbon.stole(&mut resources.oxygen);
+					let attack_to = if resources.oxygen.can_stole() {
+						sense_alive(constants, rng, bots, &pos, &alive_around)
+					} else {
+						None
+					};
+					if let Some(attack_to) = attack_to {
+						if let Some(mut attacked) = bots.get_owned(&attack_to) {
+							if let Some(amount) = ruleset.attack(resources, &mut bot, &mut attacked) {
+								observer.attack(&pos, &bot, &attack_to, &attacked, amount);
 
 								bots.set(&attack_to, attacked);
 
@@ -499,6 +779,7 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 								bot.eip = comand.goto_success;
 								return Some((pos, bot));	
 							} else {
+								bots.set(&attack_to, attacked);
 								bot.eip = comand.goto_fail;
 							}
 						} else {
@@ -514,6 +795,7 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 
 						bot.color = bot.color.interpolate(&colors::GRAY, 0.03);
 						bot.timer = bot.timer.saturating_sub(10);
+						observer.food(&pos, &bot);
 						return Some((pos, bot));
 					} else {
 						bot.eip = comand.goto_fail;
@@ -521,10 +803,10 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 				},
 				Move => {
 					if !void_around.is_empty() {
-						let new_pos = void_around.choose(rng).unwrap();
+						let new_pos = bots.remap(void_around.choose(rng).unwrap());
 						bot.color = bot.color.interpolate(&colors::WHITE, 0.03);
 						bot.eip = comand.goto_success;
-						return Some((new_pos.clone(), bot));
+						return Some((new_pos, bot));
 					} else {
 						bot.eip = comand.goto_fail;
 					}
@@ -551,238 +833,47 @@ pub fn process<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R
 		new_bot.timer = constants.live;
 		bot.protein -= new_bot.protein;
 		new_bot.eip = ProgramPos(0);
+		new_bot.last_comand = None;
 		Some((new_pos.clone(), new_bot))
 	}
 
-	fn destruct(resources: &mut Resources, bot: &mut Bot) -> Option<(Vec2i, Bot)> {
-		// info!("Destruction occured!");
-		resources.free_protein.stole_full(&mut bot.protein);
-		None
-	}
-}
-
-impl<R: Rng, G: Grid<Bot>> Window<R, G> {
-	fn new(constants: Constants, rng: R, cam: FloatImageCamera, world: World<G>) -> Self {
-		Window {
-			window: WindowBase::new(constants, rng, cam, world),
-			gesture_recognizer: GestureRecognizer::default(),
+	fn place_child<G: Grid<Bot>, O: Observer + ?Sized>(resources: &mut Resources, bots: &mut G, observer: &mut O, pos: &Vec2i, bot: &Bot, new_pos: Vec2i, mut new_bot: Bot) {
+		if bots.can(&new_pos) && !bots.has(&new_pos) {
+			observer.birth(pos, bot, &bots.remap(&new_pos), &new_bot);
+			bots.set_unchecked(&new_pos, new_bot);
+		} else {
+			resources.free_protein.stole_full(&mut new_bot.protein);
 		}
 	}
-}
 
-impl<R: Rng, G: Grid<Bot>> WindowBase<R, G> {
-	fn new(constants: Constants, rng: R, cam: FloatImageCamera, world: World<G>) -> Self {
-		let font_data = include_bytes!("Anonymous Pro.ttf");
-		WindowBase {
-			image: Image::new(&Vec2i::new(1920, 1080)),
-			bot_image: Image::new(&constants.size()),
-			world,
-			rng,
-			cam,
-			draw: FpsWithCounter::new(20),
-			simulate: FpsWithCounter::new(20),
-			last_mouse_pos: Vec2i::default(),
-			mouse_move: false,
-			current_cam_scale: 0.0,
-			text_cache: TextCache::new(Font::from_bytes(font_data as &[u8]).expect("Error constructing Font")),
-			performance_info: PerformanceInfo {
-				tps: 0,
-				steps_per_frame: 0,
-				fps: 0,
-			},
-			fps: FpsByLastTime::new(5.0),
-			tps: FpsByLastTime::new(5.0),
-			constants,
+	fn sense_alive<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, rng: &mut R, bots: &G, pos: &Vec2i, alive_around: &[Vec2i]) -> Option<Vec2i> {
+		if constants.sense <= 1 {
+			return alive_around.choose(rng).cloned();
 		}
-	}
-}
-
-impl<R: Rng, G: Grid<Bot>> MyEvents for Window<R, G> {
-	fn init(&mut self) {
-		self.window.fps.clear();
-		self.window.tps.clear();
-	}
 
-	fn update(&mut self) {
-		let mut counter = 0;
-		let rng = &mut self.window.rng;
-		let world = &mut self.window.world;
-		let constants = &self.window.constants;
-		let tps = &mut self.window.tps;
-		if let Some(d) = self.window.simulate.action(|clock| {
-			while clock.elapsed().fps() > 60.0 {
-				process_world(constants, rng, world);
-				tps.frame();
-				counter += 1;
-			}
-		}) {
-			self.window.performance_info.tps = d.fps() as usize * counter;
-			self.window.performance_info.steps_per_frame = counter;
-		}
-	}
-
-	fn draw(&mut self) {
-		let world = &self.window.world;
-		let image = &mut self.window.image;
-		let cam = &self.window.cam;
-		let text_cache = &mut self.window.text_cache;
-		let perf = &self.window.performance_info;
-		let fps = &self.window.fps;
-		let tps = &self.window.tps;
-		let bot_image = &mut self.window.bot_image;
-		if let Some(d) = self.window.draw.action(|_| {
-			image.clear(&Color::gray(0));
-			if world.bots.is_finite() {
-				bot_image.clear(&Color::gray(0));
-				for (pos, bot) in world.bots.iter() {
-					set_pixel(bot_image, &pos, &bot.color);
+		use rand::seq::IteratorRandom;
+		constants.neighborhood.cells_in_radius(pos, constants.sense).filter(|cell|
+			if bots.can(cell) {
+				match bots.get(cell) {
+					Some(around) => around.alive,
+					None => false,
 				}
-				place_repeated_scaled_image(
-					image, 
-					bot_image, 
-					&cam.from((0, 0).into()), 
-					cam.get_scale() as i32, 
-					world.bots.is_repeat_x(), 
-					world.bots.is_repeat_y()
-				);
 			} else {
-				for (pos, bot) in world.bots.iter() {
-					rect(image, &cam.from_i(pos.clone()), &cam.from_dir_i(Vec2i::new(1, 1)), &bot.color);
-				}
-			}
-			
-			let all_resources = world.bots.iter().fold(0, |acc, x| acc + x.1.protein) + world.resources.free_protein + world.resources.oxygen + world.resources.carbon;
-			let text = format!(
-				"\
-				bots: {}\n\
-				protein: {}\n\
-				oxygen: {}\n\
-				carbon: {}\n\
-				all resources: {}\n\
-				\n\
-				fps:\n\
-				potential: {}\n\
-				real:      {}\n\
-				\n\
-				simulations per second:\n\
-				potential: {}\n\
-				real:      {}\n\
-				\n\
-				simulations per frame: {}\n",
-				world.bots.len(),
-				world.resources.free_protein, 
-				world.resources.oxygen, 
-				world.resources.carbon,
-				all_resources,
-				perf.fps,
-				fps.fps() as i32,
-				perf.tps,
-				tps.fps() as i32,
-				perf.steps_per_frame,
-			);
-			let pos = Vec2i::new(7, 7);
-			let border = 4;
-			let border_vec = Vec2i::new(border, border);
-			let text_sz: f32 = 17.0;
-			draw_rect(image, &(pos.clone() - &border_vec), &(text_size(text_cache, &text, text_sz) + &border_vec + &border_vec), &Color::rgba(0, 0, 0, 150));
-			draw_text(image, text_cache, &text, text_sz, &pos, &Color::rgba(255, 255, 255, 255));
-		}) {
-			self.window.performance_info.fps = d.fps() as usize;
-		}
-		self.window.fps.frame();
-	}
-
-	fn resize_event(&mut self, new_size: Vec2i) {
-		self.window.image.resize_lazy(&new_size);
-		if self.window.cam.to(Vec2i::default()) == Vec2i::default() {
-			self.window.cam.offset(&((new_size - &(self.window.constants.size() * self.window.constants.scale)) / 2));
-		self.window.fps.clear();
-		}
-	}
-
-	fn mouse_motion_event(&mut self, pos: Vec2i, _offset: Vec2i) {
-		if self.window.mouse_move {
-			self.window.cam.offset(&(pos.clone() - &self.window.last_mouse_pos));
-		}
-		self.window.last_mouse_pos = pos;
-	}
-
-	fn mouse_button_event(&mut self, button: MouseButton, state: ButtonState, pos: Vec2i) {
-		self.window.last_mouse_pos = pos;
-		use MouseButton::*;
-		use ButtonState::*;
-		if let Left = button {
-			match state {
-				Down => {
-					self.window.mouse_move = true;
-				},
-				Up => {
-					self.window.mouse_move = false;
-				},
-				_ => {},
-			}
-		}
-	}
-
-	fn mouse_wheel_event(&mut self, pos: Vec2i, dir_vertical: MouseWheelVertical, _dir_horizontal: MouseWheelHorizontal) {
-		self.window.last_mouse_pos = pos;
-		match dir_vertical {
-			MouseWheelVertical::RotateUp => {
-				self.window.cam.scale_add(&self.window.last_mouse_pos, 1.0);
-			},
-			MouseWheelVertical::RotateDown => {
-				self.window.cam.scale_add(&self.window.last_mouse_pos, -1.0);
-			},
-			MouseWheelVertical::Nothing => {
-
-			}
-		}
-	}
-
-	fn key_event(&mut self, keycode: KeyCode, _keymods: KeyMods, state: ButtonState) {
-		if let bufdraw::ButtonState::Down = state {
-			match keycode {
-				KeyCode::R => {
-					for _ in 0..self.window.constants.bots {
-						insert_random_bot(&self.window.constants, &mut self.window.rng, &mut self.window.world);		
-					}
-				},
-				KeyCode::C => {
-					self.window.world.bots.clear();
-				},
-				_ => {},
+				false
 			}
-		}
-	}
-
-	fn touch_event(&mut self, phase: TouchPhase, id: u64, pos: &Vec2i) {
-		self.gesture_recognizer.process(&mut self.window, phase.into(), id, pos.x as f32, pos.y as f32);
-	}
-}
-
-impl<R, G> GestureEvents for WindowBase<R, G> {
-	fn touch_three_move(&mut self, _pos: &Point, offset: &Point) {
-		self.cam.offset(&offset.into_my());
-	}
-
-	fn touch_one_move(&mut self, _pos: &Point, offset: &Point) {
-		self.cam.offset(&offset.into_my());
+		).choose(rng)
 	}
 
-	fn touch_scale_start(&mut self, _pos: &Point) {
-		self.current_cam_scale = self.cam.get_scale();
-	}
-	fn touch_scale_change(&mut self, scale: f32, pos: &Point, offset: &Point) {
-		let current_scale = (self.current_cam_scale as f32 * scale) as u8;
-		self.cam.offset(&offset.into_my());
-		if current_scale != 0 {
-			self.cam.scale_new(&pos.into_my(), current_scale as f32);	
-		}
+	fn destruct(resources: &mut Resources, bot: &mut Bot) -> Option<(Vec2i, Bot)> {
+		// info!("Destruction occured!");
+		resources.free_protein.stole_full(&mut bot.protein);
+		None
 	}
 }
 
-pub fn init_world<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, mut rng: &mut R, g: G) -> World<G> {
-	let mut world = World {
+/// World with resources from constants and without bots
+pub fn empty_world<G>(constants: &Constants, g: G) -> World<G> {
+	World {
 		size: constants.size(),
 
 		resources: Resources {
@@ -792,8 +883,12 @@ pub fn init_world<R: Rng + ?Sized, G: Grid<Bot>>(constants: &Constants, mut rng:
 		},
 
 		bots: g,
-	};
+		next_lineage: 0,
+	}
+}
 
+pub fn init_world<C: Creature, R: Rng + ?Sized, G: Grid<C>>(constants: &Constants, mut rng: &mut R, g: G) -> World<G> {
+	let mut world = empty_world(constants, g);
 	for _ in 0..constants.bots {
 		insert_random_bot(constants, &mut rng, &mut world);		
 	}
@@ -807,109 +902,49 @@ impl Constants {
 	}
 }
 
-pub fn get_constants() -> Result<Constants, String> {
-	#[cfg(target_arch = "wasm32")]
-	let default_scale = "2";
-
-	#[cfg(not(target_arch = "wasm32"))]
-	let default_scale = "1";
-
-	let mut app = clap_app!(crabots =>
-		(setting: clap::AppSettings::ColorNever)
-		(version: env!("CARGO_PKG_VERSION"))
-		(author: 
-			"Ilya Sheprut ->\n\t\
-			<optozorax@gmail.com>,\n\t\
-			<github:optozorax>,\n\t\
-			<telegram:optozorax>,\n\t\
-			<website:optozorax.github.io>.")
-		(about: "\n\
-			Симуляция жизни в виде ботов. Когда-то здесь будет полноценное объяснение.\
-		")
-
-		(@arg width: -w --width +takes_value default_value("100") "Width of world grid")
-		(@arg height: -g --height +takes_value default_value("100") "Height of world grid")
-		(@arg scale: -s --scale +takes_value default_value("3.0") "Initial scale of cam")
-		(@arg image_scale: -a --image_scale +takes_value default_value(default_scale) "All image will be scaled by this value")
-		(@arg benchmark: -k --benchmark +takes_value default_value("false") "Run benchmark")
-
-		(@arg bots: -b --bots +takes_value default_value("400") "Initial count of bots")
-		(@arg protein: -p --protein +takes_value default_value("3000") "Initial count of free protein")
-		(@arg oxygen: -o --oxygen +takes_value default_value("1000") "Initial count of oxygen")
-		(@arg carbon: -c --carbon +takes_value default_value("1000") "Initial count of carbon")
-
-		(@arg die: -d --die +takes_value default_value("320") "Bots exists <die> steps after death")
-		(@arg live: -l --live +takes_value default_value("160") "Bot can live maximum this count of steps")
-		(@arg comand: -n --comand +takes_value default_value("2") "Maximum commands per step")
-		(@arg multiply: -m --multiply +takes_value default_value("4") "With this count of protein bot can multiply")
-		(@arg seed: -e --seed +takes_value default_value("92") "Seed to random generator")
-
-		(@arg topology: -t --topology +takes_value default_value("Torus") "Topology of space")
-		(@arg container: -r --container +takes_value default_value("Vec") "Container of bots")
-	);
-	#[cfg(target_arch = "wasm32")]
-	{
-		app = app.usage("index.html?help or index.html?protein=100000&topology=Infinite&a=2");
-	}
-	let matches = app.get_matches_from_safe_borrow(bufdraw::PROGRAM_PARAMETERS.iter());
-
-	let matches = match matches {
-		Ok(m) => m,
-		Err(e) => return Err(e.message),
-	};
-
-	macro_rules! arg_parse {
-		($name:literal) => {
-			matches
-				.value_of($name)
-				.ok_or(format!("No default value for {}", $name))?
-				.parse()
-				.map_err(stringify(&matches, $name))?
-		};
+impl FieldNeighborhood {
+	pub fn offsets(&self, pos: &Vec2i) -> &'static [Vec2i] {
+		use FieldNeighborhood::*;
+		match self {
+			Moore => &MOORE_NEIGHBORHOOD,
+			VonNeumann => &VON_NEUMANN_NEIGHBORHOOD,
+			Hexagonal => if pos.y & 1 == 0 {
+				&HEX_EVEN_ROW_NEIGHBORHOOD
+			} else {
+				&HEX_ODD_ROW_NEIGHBORHOOD
+			},
+		}
 	}
 
-	macro_rules! arg_match_parse {
-		($name:literal, $type:ident) => {
-			matches
-				.value_of($name)
-				.ok_or(format!("No default value for {}", $name))?
-				.parse()
-				.map_err(stringify_unit(&matches, $name, &format!("Values can only be: {:?}", $type::iter().collect::<Vec<_>>())))?
-		};
-	}
-	
-	return Ok(Constants {
-		width: arg_parse!("width"),
-		height: arg_parse!("height"),
-		scale: arg_parse!("scale"),
-		image_scale: arg_parse!("image_scale"),
-		benchmark: arg_parse!("benchmark"),
-
-		bots: arg_parse!("bots"),
-		protein: arg_parse!("protein"),
-		oxygen: arg_parse!("oxygen"),
-		carbon: arg_parse!("carbon"),
-
-		die: arg_parse!("die"),
-		live: arg_parse!("live"),
-		comand: arg_parse!("comand"),
-		multiply: arg_parse!("multiply"),
-		seed: arg_parse!("seed"),
-
-		topology: arg_match_parse!("topology", FieldTopology),
-		container: arg_match_parse!("container", FieldContainer),
-	});
-	
-	fn stringify<'a, T: std::fmt::Display>(matches: &'a clap::ArgMatches<'a>, param: &'a str) -> impl Fn(T) -> String + 'a { 
-		move |t: T| {
-			format!("Error occured while parsing arguments:\n\t{}\n\nYou provided:\n\t{}={}", t, param, matches.value_of(param).unwrap())	
+	/// Minimal count of steps between neighbors to get from `a` to `b`
+	pub fn distance(&self, a: &Vec2i, b: &Vec2i) -> i32 {
+		use FieldNeighborhood::*;
+		let dx = b.x - a.x;
+		let dy = b.y - a.y;
+		match self {
+			Moore => std::cmp::max(dx.abs(), dy.abs()),
+			VonNeumann => dx.abs() + dy.abs(),
+			Hexagonal => {
+				// Convert to axial coordinates
+				let q = |pos: &Vec2i| pos.x - (pos.y - (pos.y & 1)) / 2;
+				let dq = q(b) - q(a);
+				(dq.abs() + dy.abs() + (dq + dy).abs()) / 2
+			},
 		}
 	}
 
-	fn stringify_unit<'a>(matches: &'a clap::ArgMatches<'a>, param: &'a str, error: &'a str) -> impl Fn(()) -> String + 'a { 
-		move |_| {
-			format!("Error occured while parsing arguments:\n\t{}\n\nYou provided:\n\t{}={}", error, param, matches.value_of(param).unwrap())	
-		}
+	/// Cells on distance from 1 to `radius` around `pos`, they may not exist in the grid
+	pub fn cells_in_radius<'a>(&'a self, pos: &Vec2i, radius: u32) -> impl Iterator<Item = Vec2i> + 'a {
+		let radius = radius as i32;
+		let origin = pos.clone();
+		let center = pos.clone();
+		(-radius..=radius)
+			.flat_map(move |y| (-radius..=radius).map(move |x| Vec2i::new(x, y)))
+			.map(move |offset| offset + &origin)
+			.filter(move |cell| {
+				let distance = self.distance(&center, cell);
+				1 <= distance && distance <= radius
+			})
 	}
 }
 
@@ -924,111 +959,75 @@ pub fn gen_seed(mut seed: u64) -> [u8; 16] {
 	result
 }
 
-pub fn run_benchmark() -> String {
-	let constants = Constants {
-		width: 100,
-		height: 100,
-		scale: 1.0,
-		image_scale: 1,
-		benchmark: true,
-
-		bots: 400,
-		protein: 30000,
-		oxygen: 10000,
-		carbon: 10000,
-
-		die: 320,
-		live: 160,
-		comand: 2,
-		multiply: 4,
-		seed: 92,
-
-		topology: FieldTopology::Torus,
-		container: FieldContainer::Vec,
-	};
-	let steps = 1000;
-	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
-	let grid = VecGrid::<Bot, TorusSpace>::new(&constants.size());
-	let mut bots = 0;
-	let duration = time(|_| {
-		let mut world = init_world(&constants, &mut rng, grid);
-		for _ in 0..steps {
-			bots += world.bots.len();
-			process_world(&constants, &mut rng, &mut world);
-		}	
-	});
-	return format!("\
-		Benchmark:\n\t\
-			{} steps\n\t\
-			{} processed bots\n\t\
-			{:.2} seconds took\n\
-		Or this equals\n\t\
-			{:.1} steps per second\n\t\
-			{:.1} × 10³ bots per second.\n\
-		\n\
-		{:#?}\
-		", 
-		steps, 
-		bots, 
-		duration.seconds, 
-		steps as f64 / duration.seconds,
-		bots as f64 / duration.seconds / 1000.0, 
-		constants
-	);
-}
+/// Code which needs the grid of creatures `C` chosen by `container` and `topology` of constants.
+pub trait GridVisitor<C = Bot> {
+	type Output;
 
-pub fn main3<G: 'static + Grid<Bot>>(constants: Constants, grid: G) {
-	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
-	let camera = FloatImageCamera {
-		offset: Vec2i::default(),
-		scale: constants.scale,
-	};
-	let world = init_world(&constants, &mut rng, grid);
-	start(RescaledWindow { scale: constants.image_scale as i32, external: Window::new(constants, rng, camera, world) });
+	fn visit<G: 'static + Grid<C>>(self, constants: Constants, grid: G) -> Self::Output;
 }
 
-pub fn main2() -> Result<(), String> {
-	let constants = get_constants()?;
-
-	if constants.benchmark {
-		return Err(run_benchmark());
-	}
-
+/// Creates grid for the constants, they are checked by `Constants::validate` first.
+pub fn visit_grid<C: 'static + Clone, V: GridVisitor<C>>(constants: Constants, visitor: V) -> Result<V::Output, ConfigError> {
+	constants.validate()?;
 	let container = constants.container.clone();
 	let topology = constants.topology.clone();
 	let size = &constants.size();
 	use FieldTopology::*;
 	use FieldContainer::*;
-	match container {
+	Ok(match container {
 		HashMap => {
 			match topology {
 				Rect => 
-					main3(constants, HashMapGrid::<Bot, RectSpace>::new(size)),
+					visitor.visit(constants, HashMapGrid::<C, RectSpace>::new(size)),
 				Torus => 
-					main3(constants, HashMapGrid::<Bot, TorusSpace>::new(size)),
+					visitor.visit(constants, HashMapGrid::<C, TorusSpace>::new(size)),
 				VerticalCylinder => 
-					main3(constants, HashMapGrid::<Bot, VerticalCylinderSpace>::new(size)),
+					visitor.visit(constants, HashMapGrid::<C, VerticalCylinderSpace>::new(size)),
 				HorizontalCylinder => 
-					main3(constants, HashMapGrid::<Bot, HorizontalCylinderSpace>::new(size)),
+					visitor.visit(constants, HashMapGrid::<C, HorizontalCylinderSpace>::new(size)),
+				Mobius => 
+					visitor.visit(constants, HashMapGrid::<C, MobiusSpace>::new(size)),
+				KleinBottle => 
+					visitor.visit(constants, HashMapGrid::<C, KleinBottleSpace>::new(size)),
 				Infinite => 
-					main3(constants, HashMapGrid::<Bot, InfiniteSpace>::new_infinite()),
+					visitor.visit(constants, HashMapGrid::<C, InfiniteSpace>::new_infinite()),
 			}
 		},
 		Vec => {
-			match constants.topology {
+			match topology {
 				Rect => 
-					main3(constants, VecGrid::<Bot, RectSpace>::new(size)),
+					visitor.visit(constants, VecGrid::<C, RectSpace>::new(size)),
 				Torus => 
-					main3(constants, VecGrid::<Bot, TorusSpace>::new(size)),
+					visitor.visit(constants, VecGrid::<C, TorusSpace>::new(size)),
 				VerticalCylinder => 
-					main3(constants, VecGrid::<Bot, VerticalCylinderSpace>::new(size)),
+					visitor.visit(constants, VecGrid::<C, VerticalCylinderSpace>::new(size)),
 				HorizontalCylinder => 
-					main3(constants, VecGrid::<Bot, HorizontalCylinderSpace>::new(size)),
+					visitor.visit(constants, VecGrid::<C, HorizontalCylinderSpace>::new(size)),
+				Mobius => 
+					visitor.visit(constants, VecGrid::<C, MobiusSpace>::new(size)),
+				KleinBottle => 
+					visitor.visit(constants, VecGrid::<C, KleinBottleSpace>::new(size)),
 				Infinite => 
-					return Err("Cant use infinite topology space with Vec, use HashMap instead".to_string()),
+					return Err(ConfigError::Incompatible { keys: vec!["container", "topology"], message: "Vec can't hold infinite space".to_string() }),
 			}
 		},
-	};
-
-	Ok(())
+		Chunked => {
+			match topology {
+				Rect => 
+					visitor.visit(constants, ChunkGrid::<C, RectSpace>::new(size)),
+				Torus => 
+					visitor.visit(constants, ChunkGrid::<C, TorusSpace>::new(size)),
+				VerticalCylinder => 
+					visitor.visit(constants, ChunkGrid::<C, VerticalCylinderSpace>::new(size)),
+				HorizontalCylinder => 
+					visitor.visit(constants, ChunkGrid::<C, HorizontalCylinderSpace>::new(size)),
+				Mobius => 
+					visitor.visit(constants, ChunkGrid::<C, MobiusSpace>::new(size)),
+				KleinBottle => 
+					visitor.visit(constants, ChunkGrid::<C, KleinBottleSpace>::new(size)),
+				Infinite => 
+					visitor.visit(constants, ChunkGrid::<C, InfiniteSpace>::new_infinite()),
+			}
+		},
+	})
 }
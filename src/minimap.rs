@@ -0,0 +1,106 @@
+use crate::*;
+
+/// Position and size of the whole finite world, or of the bounding box of all bots in the infinite one
+pub fn world_area<C, G: Grid<C>>(world: &World<G>) -> (Vec2i, Vec2i) {
+	if world.bots.is_finite() {
+		return (Vec2i::default(), world.size.clone());
+	}
+	let bounds = world.bots.iter().fold(None, |bounds: Option<(Vec2i, Vec2i)>, (pos, _)| match bounds {
+		Some((min, max)) => Some((
+			Vec2i::new(std::cmp::min(min.x, pos.x), std::cmp::min(min.y, pos.y)),
+			Vec2i::new(std::cmp::max(max.x, pos.x), std::cmp::max(max.y, pos.y)),
+		)),
+		None => Some((pos.clone(), pos)),
+	});
+	match bounds {
+		Some((min, max)) => {
+			let size = max - &min + &Vec2i::new(1, 1);
+			(min, size)
+		},
+		None => (Vec2i::default(), world.size.clone()),
+	}
+}
+
+/// Maximal side of the minimap in pixels
+const MINIMAP_SIZE: i32 = 200;
+
+/// Place of the minimap on the screen and the part of the world shown on it.
+pub struct Minimap {
+	pub pos: Vec2i,
+	pub size: Vec2i,
+	pub area_pos: Vec2i,
+	pub area_size: Vec2i,
+}
+
+impl Minimap {
+	/// Minimap in the bottom right corner of the screen. Covers the whole finite world, or the bounding box of all bots in the infinite one.
+	pub fn new<C, G: Grid<C>>(world: &World<G>, screen: &Vec2i) -> Self {
+		let (area_pos, area_size) = world_area(world);
+		let scale = MINIMAP_SIZE as f32 / std::cmp::max(area_size.x, area_size.y) as f32;
+		let size = Vec2i::new(
+			std::cmp::max((area_size.x as f32 * scale) as i32, 1),
+			std::cmp::max((area_size.y as f32 * scale) as i32, 1),
+		);
+		Minimap {
+			pos: screen.clone() - &size - &Vec2i::new(3, 3),
+			size,
+			area_pos,
+			area_size,
+		}
+	}
+
+	pub fn contains(&self, screen_pos: &Vec2i) -> bool {
+		let pos = screen_pos.clone() - &self.pos;
+		0 <= pos.x && pos.x < self.size.x && 0 <= pos.y && pos.y < self.size.y
+	}
+
+	/// Position in the world under the point of the minimap
+	pub fn to_world(&self, screen_pos: &Vec2i) -> (f32, f32) {
+		let pos = screen_pos.clone() - &self.pos;
+		(
+			self.area_pos.x as f32 + pos.x as f32 * self.area_size.x as f32 / self.size.x as f32,
+			self.area_pos.y as f32 + pos.y as f32 * self.area_size.y as f32 / self.size.y as f32,
+		)
+	}
+
+	/// Point of the minimap for the position in the world
+	pub fn from_world(&self, (x, y): (f32, f32)) -> Vec2i {
+		Vec2i::new(
+			self.pos.x + ((x - self.area_pos.x as f32) * self.size.x as f32 / self.area_size.x as f32) as i32,
+			self.pos.y + ((y - self.area_pos.y as f32) * self.size.y as f32 / self.area_size.y as f32) as i32,
+		)
+	}
+
+	/// Some part of the shown area is outside of the screen
+	pub fn is_needed(&self, cam: &FloatImageCamera, screen: &Vec2i) -> bool {
+		let (min_x, min_y) = cam.to_f(Vec2i::default());
+		let (max_x, max_y) = cam.to_f(screen.clone());
+		let area_max = self.area_pos.clone() + &self.area_size;
+		min_x > self.area_pos.x as f32 || min_y > self.area_pos.y as f32 || max_x < area_max.x as f32 || max_y < area_max.y as f32
+	}
+
+	/// Draws bots and the rectangle of the part of the world visible on the screen
+	pub fn draw<C, G: Grid<C>, F: Fn(&C) -> Color>(&self, image: &mut Image, cam: &FloatImageCamera, world: &World<G>, bot_color: F) {
+		draw_rect(image, &self.pos, &self.size, &Color::rgba(0, 0, 0, 200));
+		for (pos, bot) in world.bots.iter() {
+			let point = self.from_world((pos.x as f32, pos.y as f32));
+			if self.contains(&point) {
+				set_pixel(image, &point, &bot_color(bot));
+			}
+		}
+
+		let screen = Vec2i::new(image.get_width() as i32, image.get_height() as i32);
+		let clamp = |point: Vec2i| Vec2i::new(
+			point.x.clamp(self.pos.x, self.pos.x + self.size.x - 1),
+			point.y.clamp(self.pos.y, self.pos.y + self.size.y - 1),
+		);
+		let min = clamp(self.from_world(cam.to_f(Vec2i::default())));
+		let max = clamp(self.from_world(cam.to_f(screen)));
+		let size = max.clone() - &min + &Vec2i::new(1, 1);
+		let color = Color::rgba(255, 255, 0, 255);
+		rect(image, &min, &Vec2i::new(size.x, 1), &color);
+		rect(image, &min, &Vec2i::new(1, size.y), &color);
+		rect(image, &Vec2i::new(min.x, max.y), &Vec2i::new(size.x, 1), &color);
+		rect(image, &Vec2i::new(max.x, min.y), &Vec2i::new(1, size.y), &color);
+	}
+}
@@ -0,0 +1,162 @@
+use std::ops::RangeInclusive;
+use crate::*;
+
+/// Draws creatures with any scale of the camera. When the cell is smaller than a pixel, colors of all cells in the pixel are averaged.
+///
+/// Hexagonal cells are drawn as squares, odd rows are shifted by half of the cell to the right. This shift is ignored when cells are smaller than a pixel.
+pub fn draw_bots<C, G: Grid<C>, F: Fn(&C) -> Color>(image: &mut Image, cam: &FloatImageCamera, world: &World<G>, hexagonal: bool, bot_color: F) {
+	if cam.scale >= 1.0 {
+		draw_magnified(image, cam, world, hexagonal, bot_color);
+	} else if world.bots.is_finite() {
+		draw_downsampled(image, cam, world, bot_color);
+	} else {
+		draw_downsampled_infinite(image, cam, world, bot_color);
+	}
+}
+
+fn screen_size(image: &Image) -> Vec2i {
+	Vec2i::new(image.get_width() as i32, image.get_height() as i32)
+}
+
+/// Indexes of copies of the world visible on the screen along one axis
+fn visible_copies(repeat: bool, origin: f32, tile: f32, screen: i32) -> RangeInclusive<i32> {
+	if repeat && tile > 0.0 {
+		(-origin / tile).floor() as i32 - 1..=((screen as f32 - origin) / tile).floor() as i32
+	} else {
+		0..=0
+	}
+}
+
+/// Draws only cells on the screen when they are fewer than bots, otherwise draws each bot in each visible copy of the world
+fn draw_magnified<C, G: Grid<C>, F: Fn(&C) -> Color>(image: &mut Image, cam: &FloatImageCamera, world: &World<G>, hexagonal: bool, bot_color: F) {
+	let screen = screen_size(image);
+	let (left, top) = cam.to_f(Vec2i::default());
+	let (right, bottom) = cam.to_f(screen.clone());
+	// Shifted hexagonal row starts half of the cell to the right, so one more cell is needed at the left
+	let min = Vec2i::new(left.floor() as i32 - 1, top.floor() as i32);
+	let max = Vec2i::new(right.ceil() as i32, bottom.ceil() as i32);
+	let visible_cells = i64::from(max.x - min.x + 1) * i64::from(max.y - min.y + 1);
+	if visible_cells >= world.bots.len() as i64 {
+		draw_magnified_bots(image, cam, world, hexagonal, bot_color);
+		return;
+	}
+
+	for y in min.y..=max.y {
+		for x in min.x..=max.x {
+			let pos = Vec2i::new(x, y);
+			if !world.bots.can(&pos) {
+				continue;
+			}
+			if let Some(bot) = world.bots.get(&pos) {
+				let shift = if hexagonal && y & 1 == 1 { 0.5 } else { 0.0 };
+				let (start, cell) = cam.cell_rect((x as f32 + shift, y as f32));
+				rect(image, &start, &cell, &bot_color(bot));
+			}
+		}
+	}
+}
+
+fn draw_magnified_bots<C, G: Grid<C>, F: Fn(&C) -> Color>(image: &mut Image, cam: &FloatImageCamera, world: &World<G>, hexagonal: bool, bot_color: F) {
+	let size = &world.size;
+	let screen = screen_size(image);
+	let origin = cam.from_f((0.0, 0.0));
+	let copies_x = visible_copies(world.bots.is_repeat_x(), origin.0, size.x as f32 * cam.scale, screen.x);
+	let copies_y = visible_copies(world.bots.is_repeat_y(), origin.1, size.y as f32 * cam.scale, screen.y);
+
+	for (pos, bot) in world.bots.iter() {
+		let color = bot_color(bot);
+		for copy_y in copies_y.clone() {
+			for copy_x in copies_x.clone() {
+				let y = if world.bots.is_mirrored_x() && copy_x & 1 == 1 { size.y - 1 - pos.y } else { pos.y };
+				let shift = if hexagonal && y & 1 == 1 { 0.5 } else { 0.0 };
+				let (start, cell) = cam.cell_rect((
+					(pos.x + copy_x * size.x) as f32 + shift,
+					(y + copy_y * size.y) as f32,
+				));
+				if start.x < screen.x && start.y < screen.y && start.x + cell.x > 0 && start.y + cell.y > 0 {
+					rect(image, &start, &cell, &color);
+				}
+			}
+		}
+	}
+}
+
+/// Pixel of each cell along one axis, when the first cell starts at `phase` inside the first pixel. Also returns count of cells in each pixel.
+fn downsample_axis(len: i32, scale: f32, phase: f32) -> (Vec<usize>, Vec<u32>) {
+	let pixels: Vec<usize> = (0..len).map(|i| (i as f32 * scale + phase).floor() as usize).collect();
+	let mut cells = vec![0; pixels.last().map_or(0, |last| last + 1)];
+	for pixel in &pixels {
+		cells[*pixel] += 1;
+	}
+	(pixels, cells)
+}
+
+/// Downsamples one period of the world once, then places it for each visible copy
+fn draw_downsampled<C, G: Grid<C>, F: Fn(&C) -> Color>(image: &mut Image, cam: &FloatImageCamera, world: &World<G>, bot_color: F) {
+	let size = &world.size;
+	// Mirrored copy is placed right after the world, so the image repeats with period of two worlds
+	let period = if world.bots.is_mirrored_x() { Vec2i::new(size.x * 2, size.y) } else { size.clone() };
+	let origin = cam.from_f((0.0, 0.0));
+	let (pixels_x, cells_x) = downsample_axis(period.x, cam.scale, origin.0 - origin.0.floor());
+	let (pixels_y, cells_y) = downsample_axis(period.y, cam.scale, origin.1 - origin.1.floor());
+
+	let width = cells_x.len();
+	let mut sums = vec![ColorSum::default(); width * cells_y.len()];
+	for (pos, bot) in world.bots.iter() {
+		let color = bot_color(bot);
+		sums[pixels_x[pos.x as usize] + pixels_y[pos.y as usize] * width].add(&color);
+		if world.bots.is_mirrored_x() {
+			let mirrored = Vec2i::new(pos.x + size.x, size.y - 1 - pos.y);
+			sums[pixels_x[mirrored.x as usize] + pixels_y[mirrored.y as usize] * width].add(&color);
+		}
+	}
+
+	let screen = screen_size(image);
+	let tile = (period.x as f32 * cam.scale, period.y as f32 * cam.scale);
+	for copy_y in visible_copies(world.bots.is_repeat_y(), origin.1, tile.1, screen.y) {
+		for copy_x in visible_copies(world.bots.is_repeat_x(), origin.0, tile.0, screen.x) {
+			let copy_pos = Vec2i::new(
+				(origin.0 + copy_x as f32 * tile.0).floor() as i32,
+				(origin.1 + copy_y as f32 * tile.1).floor() as i32,
+			);
+			for (index, sum) in sums.iter().enumerate() {
+				if sum.r + sum.g + sum.b == 0 {
+					continue;
+				}
+				let (x, y) = (index % width, index / width);
+				let pos = Vec2i::new(copy_pos.x + x as i32, copy_pos.y + y as i32);
+				if 0 <= pos.x && pos.x < screen.x && 0 <= pos.y && pos.y < screen.y {
+					set_pixel(image, &pos, &sum.average(cells_x[x] * cells_y[y]));
+				}
+			}
+		}
+	}
+}
+
+/// Infinite world doesn't repeat, so bots are summed right into pixels of the screen
+fn draw_downsampled_infinite<C, G: Grid<C>, F: Fn(&C) -> Color>(image: &mut Image, cam: &FloatImageCamera, world: &World<G>, bot_color: F) {
+	let screen = screen_size(image);
+	let width = screen.x as usize;
+	let mut sums = vec![ColorSum::default(); width * screen.y as usize];
+	for (pos, bot) in world.bots.iter() {
+		let (x, y) = cam.from_f((pos.x as f32, pos.y as f32));
+		let (x, y) = (x.floor() as i32, y.floor() as i32);
+		if 0 <= x && x < screen.x && 0 <= y && y < screen.y {
+			sums[x as usize + y as usize * width].add(&bot_color(bot));
+		}
+	}
+
+	// Count of integer cells whose pixel is `pixel`
+	let cells = |pixel: i32, offset: i32| {
+		let first = |pixel: i32| ((pixel - offset) as f32 / cam.scale).ceil() as i32;
+		(first(pixel + 1) - first(pixel)) as u32
+	};
+	for (index, sum) in sums.iter().enumerate() {
+		if sum.r + sum.g + sum.b == 0 {
+			continue;
+		}
+		let pos = Vec2i::new((index % width) as i32, (index / width) as i32);
+		let count = cells(pos.x, cam.offset.x) * cells(pos.y, cam.offset.y);
+		set_pixel(image, &pos, &sum.average(std::cmp::max(count, 1)));
+	}
+}
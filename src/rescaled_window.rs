@@ -1,4 +1,6 @@
 use crate::*;
+// Events are passed through unchanged, so positions stay in types of `bufdraw`
+use bufdraw::vec::Vec2i;
 
 pub struct RescaledWindow<T> {
 	pub scale: i32,
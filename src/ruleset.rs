@@ -0,0 +1,82 @@
+use crate::*;
+
+/// Rules of the step of `Bot`, they are its `Creature::Rules`. Every method has the default implementation with the original rules, so a variant overrides only what differs.
+///
+/// Variant is passed to `process_world` as `&dyn Ruleset`, so it can be chosen at runtime.
+pub trait Ruleset {
+	/// Called at the start of each step, alive bot dies and dead bot is destroyed when timer reaches zero
+	fn age(&self, _constants: &Constants, bot: &mut Bot) {
+		bot.timer = bot.timer.saturating_sub(1);
+	}
+
+	/// Gives protein to the bot, returns `false` if it's not possible
+	fn photosynthesis(&self, resources: &mut Resources, bot: &mut Bot) -> bool {
+		if resources.free_protein.can_stole() && resources.carbon.can_stole() {
+			bot.protein.stole(&mut resources.free_protein);
+			resources.oxygen.stole(&mut resources.carbon);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Takes protein from the victim, returns its amount or `None` if attack failed. Called only when there is free oxygen.
+	fn attack(&self, resources: &mut Resources, attacker: &mut Bot, victim: &mut Bot) -> Option<u32> {
+		if victim.protein.can_stole() {
+			attacker.protein.stole(&mut victim.protein);
+			resources.carbon.stole(&mut resources.oxygen);
+			Some(1)
+		} else {
+			None
+		}
+	}
+}
+
+/// Original rules of crabots.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DefaultRuleset;
+
+impl Ruleset for DefaultRuleset {}
+
+/// Steps don't make alive bots older, only food shortens their life. Dead bots still decay.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AgelessRuleset;
+
+impl Ruleset for AgelessRuleset {
+	fn age(&self, constants: &Constants, bot: &mut Bot) {
+		if !bot.alive {
+			DefaultRuleset.age(constants, bot);
+		}
+	}
+}
+
+/// Attacks always fail, bots get protein only from photosynthesis and dead bots.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct PeacefulRuleset;
+
+impl Ruleset for PeacefulRuleset {
+	fn attack(&self, _resources: &mut Resources, _attacker: &mut Bot, _victim: &mut Bot) -> Option<u32> {
+		None
+	}
+}
+
+/// Rulesets which can be chosen in the command line and config
+#[derive(Clone, enum_utils::FromStr, enum_utils::IterVariants, Debug, serde::Serialize)]
+pub enum RulesetVariant {
+	Default,
+	Ageless,
+	Peaceful,
+}
+
+impl RulesetVariant {
+	pub fn ruleset(&self) -> &'static dyn Ruleset {
+		match self {
+			RulesetVariant::Default => &DefaultRuleset,
+			RulesetVariant::Ageless => &AgelessRuleset,
+			RulesetVariant::Peaceful => &PeacefulRuleset,
+		}
+	}
+}
+
+/// `DefaultRuleset` already coerced to the type of `Creature::Rules` of `Bot`, so it can be passed to `process_world` directly
+pub const DEFAULT_RULESET: &dyn Ruleset = &DefaultRuleset;
@@ -0,0 +1,45 @@
+//! Drawing on `bufdraw` images with the types of this crate.
+
+use crate::*;
+
+impl From<Vec2i> for bufdraw::vec::Vec2i {
+	fn from(pos: Vec2i) -> Self {
+		bufdraw::vec::Vec2i::new(pos.x, pos.y)
+	}
+}
+
+impl From<bufdraw::vec::Vec2i> for Vec2i {
+	fn from(pos: bufdraw::vec::Vec2i) -> Self {
+		Vec2i::new(pos.x, pos.y)
+	}
+}
+
+impl From<&Color> for bufdraw::image::Color {
+	fn from(color: &Color) -> Self {
+		bufdraw::image::Color::rgba(color.r, color.g, color.b, color.a)
+	}
+}
+
+pub fn set_pixel(image: &mut Image, pos: &Vec2i, color: &Color) {
+	bufdraw::image::set_pixel(image, &pos.clone().into(), &color.into());
+}
+
+pub fn rect(image: &mut Image, pos: &Vec2i, size: &Vec2i, color: &Color) {
+	bufdraw::image::rect(image, &pos.clone().into(), &size.clone().into(), &color.into());
+}
+
+pub fn draw_rect(image: &mut Image, pos: &Vec2i, size: &Vec2i, color: &Color) {
+	bufdraw::image::draw_rect(image, &pos.clone().into(), &size.clone().into(), &color.into());
+}
+
+pub fn place_image(image: &mut Image, other: &Image, pos: &Vec2i) {
+	bufdraw::image::place_image(image, other, &pos.clone().into());
+}
+
+pub fn text_size(text_cache: &TextCache, text: &str, size: f32) -> Vec2i {
+	bufdraw::text::text_size(text_cache, text, size).into()
+}
+
+pub fn draw_text(image: &mut Image, text_cache: &mut TextCache, text: &str, size: f32, pos: &Vec2i, color: &Color) {
+	bufdraw::text::draw_text(image, text_cache, text, size, &pos.clone().into(), &color.into());
+}
@@ -61,9 +61,9 @@ impl TextWindowBase {
 	pub fn new(text: String, cam: FloatImageCamera) -> Self {
 		let font_data = include_bytes!("Anonymous Pro.ttf");
 		TextWindowBase {
-			image: Image::new(&Vec2i::new(1920, 1080)),
+			image: Image::new(&Vec2i::new(1920, 1080).into()),
 			cam,
-			text_image: Image::new(&Vec2i::new(1920, 1080)),
+			text_image: Image::new(&Vec2i::new(1920, 1080).into()),
 			redraw: true,
 			text,
 			last_mouse_pos: Vec2i::default(),
@@ -78,8 +78,8 @@ impl TextWindowBase {
 	fn redraw_image(&mut self) {
 		let size_text = 24.0 * self.cam.get_scale();
 		let size = text_size(&self.text_cache, &self.text, size_text);
-		self.text_image.resize_lazy(&size);
-		self.text_image.clear(&Color::rgba(0, 0, 0, 255));
+		self.text_image.resize_lazy(&size.into());
+		self.text_image.clear(&(&Color::rgba(0, 0, 0, 255)).into());
 		draw_text(
 			&mut self.text_image,
 			&mut self.text_cache, 
@@ -93,7 +93,7 @@ impl TextWindowBase {
 
 impl MyEvents for TextWindow {
 	fn draw(&mut self) {
-		self.window.image.clear(&Color::gray(0));
+		self.window.image.clear(&(&Color::gray(0)).into());
 		if self.window.redraw {
 			self.window.redraw_image();
 			self.window.redraw = false
@@ -101,23 +101,25 @@ impl MyEvents for TextWindow {
 		place_image(&mut self.window.image, &self.window.text_image, &self.window.cam.from(Vec2i::default()));
 	}
 
-	fn resize_event(&mut self, new_size: Vec2i) {
-		self.window.image.resize_lazy(&new_size);
+	fn resize_event(&mut self, new_size: bufdraw::vec::Vec2i) {
+		let new_size: Vec2i = new_size.into();
+		self.window.image.resize_lazy(&new_size.clone().into());
 		if self.window.cam.to(Vec2i::default()) == Vec2i::default() {
 			self.window.cam.offset(&((new_size - &text_size(&self.window.text_cache, &self.window.text, 24.0 * self.window.cam.get_scale())) / 2));
 			self.window.redraw = true;
 		}
 	}
 
-	fn mouse_motion_event(&mut self, pos: Vec2i, _offset: Vec2i) {
+	fn mouse_motion_event(&mut self, pos: bufdraw::vec::Vec2i, _offset: bufdraw::vec::Vec2i) {
+		let pos: Vec2i = pos.into();
 		if self.window.mouse_move {
 			self.window.cam.offset(&(pos.clone() - &self.window.last_mouse_pos));
 		}
 		self.window.last_mouse_pos = pos;
 	}
 
-	fn mouse_button_event(&mut self, button: MouseButton, state: ButtonState, pos: Vec2i) {
-		self.window.last_mouse_pos = pos;
+	fn mouse_button_event(&mut self, button: MouseButton, state: ButtonState, pos: bufdraw::vec::Vec2i) {
+		self.window.last_mouse_pos = pos.into();
 		use MouseButton::*;
 		use ButtonState::*;
 		if let Left = button {
@@ -133,8 +135,8 @@ impl MyEvents for TextWindow {
 		}
 	}
 
-	fn mouse_wheel_event(&mut self, pos: Vec2i, dir_vertical: MouseWheelVertical, _dir_horizontal: MouseWheelHorizontal) {
-		self.window.last_mouse_pos = pos;
+	fn mouse_wheel_event(&mut self, pos: bufdraw::vec::Vec2i, dir_vertical: MouseWheelVertical, _dir_horizontal: MouseWheelHorizontal) {
+		self.window.last_mouse_pos = pos.into();
 		match dir_vertical {
 			MouseWheelVertical::RotateUp => {
 				self.window.cam.scale_mul(&self.window.last_mouse_pos, 1.2);
@@ -150,7 +152,7 @@ impl MyEvents for TextWindow {
 		}
 	}
 
-	fn touch_event(&mut self, phase: TouchPhase, id: u64, pos: &Vec2i) {
+	fn touch_event(&mut self, phase: TouchPhase, id: u64, pos: &bufdraw::vec::Vec2i) {
 		self.gesture_recognizer.process(&mut self.window, phase.into(), id, pos.x as f32, pos.y as f32);
 	}
 }
@@ -0,0 +1,95 @@
+use crate::*;
+
+/// What left mouse button does in the interactive window.
+#[derive(Clone, Copy, PartialEq, enum_utils::FromStr, enum_utils::IterVariants, Debug)]
+pub enum Tool {
+	/// Click selects a bot, drag moves the camera
+	Inspect,
+	/// Random bots, each one starts a new lineage
+	RandomBots,
+	/// Copies of the selected bot
+	Genome,
+	/// Removes bots, their protein is returned to free protein
+	Erase,
+	/// Dead bots with protein taken from free protein
+	Food,
+}
+
+#[derive(Clone)]
+pub struct Brush {
+	pub tool: Tool,
+	/// Radius in cells
+	pub size: u32,
+}
+
+/// Protein in each dead bot painted by `Tool::Food`
+pub const FOOD_PROTEIN: u32 = 10;
+
+impl Tool {
+	pub fn next(self) -> Self {
+		let mut tools = Self::iter().cycle().skip_while(|tool| *tool != self);
+		tools.next();
+		tools.next().unwrap()
+	}
+}
+
+/// Applies the brush to cells around `center`. Occupied cells are changed only by `Tool::Erase`.
+pub fn paint<R: Rng + ?Sized, G: Grid<Bot>, O: Observer + ?Sized>(
+	brush: &Brush,
+	constants: &Constants,
+	rng: &mut R,
+	world: &mut World<G>,
+	observer: &mut O,
+	genome: Option<&Bot>,
+	center: &Vec2i,
+) {
+	let cells = std::iter::once(center.clone()).chain(constants.neighborhood.cells_in_radius(center, brush.size.saturating_sub(1)));
+	for cell in cells {
+		if !world.bots.can(&cell) {
+			continue;
+		}
+		let pos = world.bots.remap(&cell);
+		if let Tool::Erase = brush.tool {
+			if let Some(mut bot) = world.bots.get_owned(&pos) {
+				observer.destruction(&pos, &bot);
+				world.resources.free_protein.stole_full(&mut bot.protein);
+			}
+			continue;
+		}
+		if world.bots.has(&pos) {
+			continue;
+		}
+
+		let mut bot = match brush.tool {
+			Tool::Inspect | Tool::Erase => return,
+			Tool::RandomBots => {
+				let mut bot = Bot::make_random(rng);
+				bot.lineage = world.next_lineage;
+				world.next_lineage += 1;
+				bot
+			},
+			Tool::Genome => match genome {
+				Some(genome) => {
+					let mut bot = genome.clone();
+					bot.alive = true;
+					bot.protein = 0;
+					bot.eip = ProgramPos(0);
+					bot.last_comand = None;
+					bot
+				},
+				None => return,
+			},
+			Tool::Food => {
+				let mut bot = Bot::make_random(rng);
+				bot.alive = false;
+				bot.color = colors::BLACK;
+				let amount = std::cmp::min(FOOD_PROTEIN, world.resources.free_protein);
+				world.resources.free_protein -= amount;
+				bot.protein = amount;
+				bot
+			},
+		};
+		bot.timer = if bot.alive { constants.live } else { constants.die };
+		world.bots.set_unchecked(&pos, bot);
+	}
+}
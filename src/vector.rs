@@ -0,0 +1,67 @@
+use std::ops::*;
+
+/// Integer 2D vector for positions in the world and on the screen.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2i {
+	pub x: i32,
+	pub y: i32,
+}
+
+impl Vec2i {
+	pub fn new(x: i32, y: i32) -> Self {
+		Vec2i { x, y }
+	}
+}
+
+impl From<(i32, i32)> for Vec2i {
+	fn from((x, y): (i32, i32)) -> Self {
+		Vec2i::new(x, y)
+	}
+}
+
+impl Add<&Vec2i> for Vec2i {
+	type Output = Vec2i;
+
+	fn add(self, other: &Vec2i) -> Vec2i {
+		Vec2i::new(self.x + other.x, self.y + other.y)
+	}
+}
+
+impl Sub<&Vec2i> for Vec2i {
+	type Output = Vec2i;
+
+	fn sub(self, other: &Vec2i) -> Vec2i {
+		Vec2i::new(self.x - other.x, self.y - other.y)
+	}
+}
+
+impl Mul<i32> for Vec2i {
+	type Output = Vec2i;
+
+	fn mul(self, other: i32) -> Vec2i {
+		Vec2i::new(self.x * other, self.y * other)
+	}
+}
+
+impl Mul<f32> for Vec2i {
+	type Output = Vec2i;
+
+	fn mul(self, other: f32) -> Vec2i {
+		Vec2i::new((self.x as f32 * other) as i32, (self.y as f32 * other) as i32)
+	}
+}
+
+impl Div<i32> for Vec2i {
+	type Output = Vec2i;
+
+	fn div(self, other: i32) -> Vec2i {
+		Vec2i::new(self.x / other, self.y / other)
+	}
+}
+
+impl AddAssign<Vec2i> for Vec2i {
+	fn add_assign(&mut self, other: Vec2i) {
+		self.x += other.x;
+		self.y += other.y;
+	}
+}
@@ -0,0 +1,114 @@
+use crate::*;
+
+/// What is shown by the color of each bot.
+#[derive(Clone, Copy, PartialEq, enum_utils::FromStr, enum_utils::IterVariants, Debug)]
+pub enum ViewMode {
+	/// Inherited color with command tints
+	Color,
+	Protein,
+	/// Remaining steps until death, or until destruction for dead bots
+	Timer,
+	Alive,
+	/// Command executed on the last step
+	Comand,
+	/// Same genomes have same colors
+	Genome,
+	/// Bots descended from the same random bot have same colors
+	Lineage,
+}
+
+const NO_COMAND: Color = Color { r: 40, g: 40, b: 40, a: 255 };
+
+impl ViewMode {
+	pub fn next(self) -> Self {
+		let mut modes = Self::iter().cycle().skip_while(|mode| *mode != self);
+		modes.next();
+		modes.next().unwrap()
+	}
+
+	pub fn color(&self, bot: &Bot, constants: &Constants) -> Color {
+		match self {
+			ViewMode::Color => bot.color.clone(),
+			ViewMode::Protein => heatmap(bot.protein as f32 / (10 * constants.multiply) as f32),
+			ViewMode::Timer => if bot.alive {
+				colors::BLACK.interpolate(&colors::GREEN, bot.timer as f32 / constants.live as f32)
+			} else {
+				colors::BLACK.interpolate(&colors::GRAY, bot.timer as f32 / constants.die as f32)
+			},
+			ViewMode::Alive => if bot.alive { colors::GREEN } else { colors::GRAY },
+			ViewMode::Comand => match (bot.alive, bot.last_comand) {
+				(true, Some(comand)) => comand_color(comand),
+				_ => NO_COMAND,
+			},
+			ViewMode::Genome => hash_color(bot.program.iter().fold(0u64, |hash, comand| {
+				let value = comand.comand as u64 + (comand.goto_success.0 as u64) * 8 + (comand.goto_fail.0 as u64) * 8 * PROGRAM_SIZE as u64;
+				hash.wrapping_mul(0x100_0000_01b3) ^ value
+			})),
+			ViewMode::Lineage => hash_color(u64::from(bot.lineage)),
+		}
+	}
+
+	/// Colors with their meaning, shown in the HUD
+	pub fn legend(&self, constants: &Constants) -> Vec<(Color, String)> {
+		match self {
+			ViewMode::Color => vec![
+				(colors::BLUE, "multiply".to_string()),
+				(colors::GREEN, "photosynthesis".to_string()),
+				(colors::RED, "attack".to_string()),
+				(colors::GRAY, "food".to_string()),
+				(colors::WHITE, "move".to_string()),
+				(colors::BLACK, "dead".to_string()),
+			],
+			ViewMode::Protein => {
+				let max = 10 * constants.multiply;
+				(0..=4).map(|i| (heatmap(i as f32 / 4.0), format!("{} protein", max * i / 4))).collect()
+			},
+			ViewMode::Timer => vec![
+				(colors::GREEN, format!("alive, {} steps left", constants.live)),
+				(colors::BLACK, "0 steps left".to_string()),
+				(colors::GRAY, format!("dead, {} steps left", constants.die)),
+			],
+			ViewMode::Alive => vec![
+				(colors::GREEN, "alive".to_string()),
+				(colors::GRAY, "dead".to_string()),
+			],
+			ViewMode::Comand => {
+				use Comands::*;
+				let mut result: Vec<(Color, String)> = [Multiply, Photosynthesis, Attack, Food, Move].iter()
+					.map(|comand| (comand_color(*comand), format!("{:?}", comand)))
+					.collect();
+				result.push((NO_COMAND, "dead or none".to_string()));
+				result
+			},
+			ViewMode::Genome => vec![(colors::WHITE, "same color, same genome".to_string())],
+			ViewMode::Lineage => vec![(colors::WHITE, "same color, same ancestor".to_string())],
+		}
+	}
+}
+
+pub(crate) fn comand_color(comand: Comands) -> Color {
+	use Comands::*;
+	match comand {
+		Multiply => colors::BLUE,
+		Photosynthesis => colors::GREEN,
+		Attack => colors::RED,
+		Food => colors::GRAY,
+		Move => colors::WHITE,
+	}
+}
+
+/// Black, red, yellow, white for `value` from 0 to 1
+fn heatmap(value: f32) -> Color {
+	let value = value.clamp(0.0, 1.0) * 3.0;
+	let channel = |from: f32| ((value - from).clamp(0.0, 1.0) * 255.0) as u8;
+	Color::rgba(channel(0.0), channel(1.0), channel(2.0), 255)
+}
+
+fn hash_color(value: u64) -> Color {
+	let mut hash = value ^ 0xcbf2_9ce4_8422_2325;
+	hash ^= hash >> 33;
+	hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+	hash ^= hash >> 33;
+	// Not too dark, to be seen on the black background
+	Color::rgba(64 + (hash & 0xbf) as u8, 64 + ((hash >> 8) & 0xbf) as u8, 64 + ((hash >> 16) & 0xbf) as u8, 255)
+}
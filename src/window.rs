@@ -0,0 +1,909 @@
+use crate::*;
+
+pub struct PerformanceInfo {
+	pub tps: usize,
+	pub steps_per_frame: usize,
+	pub fps: usize,
+}
+
+#[derive(Delegate)]
+#[delegate(ImageTrait, target = "window")]
+pub struct Window<R, G, C: WindowCreature = Bot> {
+	pub window: WindowBase<R, G, C>,
+	pub gesture_recognizer: GestureRecognizer,
+}
+
+#[derive(Delegate)]
+#[delegate(ImageTrait, target = "image")]
+pub struct WindowBase<R, G, C: WindowCreature = Bot> {
+	pub image: Image,
+
+	pub world: World<G>,
+	pub rng: R,
+	pub cam: FloatImageCamera,
+
+	pub draw: FpsWithCounter,
+	pub simulate: FpsWithCounter,
+
+	pub last_mouse_pos: Vec2i,
+	pub mouse_down_pos: Vec2i,
+	pub mouse_move: bool,
+	pub current_cam_scale: f32,
+
+	pub text_cache: TextCache,
+
+	pub performance_info: PerformanceInfo,
+
+	pub fps: FpsByLastTime,
+	pub tps: FpsByLastTime,
+
+	pub constants: Constants,
+	/// Selected creature, it is followed by the camera and shown in the inspect panel of bots
+	pub selected: BotTracker,
+
+	pub paused: bool,
+	/// Ticks to make while paused
+	pub single_steps: usize,
+	/// Maximum ticks per second, `None` means as many as fit into a frame
+	pub tps_limit: Option<u32>,
+	/// Fractional ticks accumulated between frames when `tps_limit` is set
+	pub tick_debt: f64,
+	/// Ticks made since the start
+	pub tick: u64,
+
+	pub history: History,
+	pub follow: Follow,
+
+	pub panels: C::Panels,
+}
+
+/// Creature which can be shown in the interactive window. The window moves the camera, selects and follows creatures, draws charts and controls the speed for any of them, other controls are added by the hooks.
+#[allow(unused_variables)]
+pub trait WindowCreature: Creature + Clone + 'static {
+	/// State of the controls which exist only for this kind of creatures
+	type Panels: Default;
+
+	/// Rules of the step chosen by the constants
+	fn rules(constants: &Constants) -> &Self::Rules;
+
+	/// Color on the screen, it may depend on the state of the panels
+	fn view_color(&self, panels: &Self::Panels, constants: &Constants) -> Color {
+		self.color()
+	}
+
+	/// Creatures with the same lineage are followed together after the selected one
+	fn lineage(&self) -> Option<u32> {
+		None
+	}
+
+	/// Protein held by the creature, it is counted in all resources of the HUD
+	fn protein(&self) -> u32 {
+		0
+	}
+
+	/// Lines of the HUD about the panels
+	fn hud_text<R, G: Grid<Self>>(window: &WindowBase<R, G, Self>) -> String {
+		String::new()
+	}
+
+	/// Draws panels under the HUD at `pos` and the panel of the selected creature
+	fn draw_panels(image: &mut Image, text_cache: &mut TextCache, panels: &Self::Panels, constants: &Constants, pos: &Vec2i, selected: Option<(&Vec2i, &Self)>) {}
+
+	/// Handles left mouse button instead of the camera and the selection, returns false if it is not handled
+	fn left_button<R: Rng, G: Grid<Self>>(window: &mut WindowBase<R, G, Self>, down: bool, pos: &Vec2i) -> bool {
+		false
+	}
+
+	fn mouse_motion<R: Rng, G: Grid<Self>>(window: &mut WindowBase<R, G, Self>, pos: &Vec2i) {}
+
+	/// Handles the key which is not used by the window itself
+	fn key_down<R: Rng, G: Grid<Self>>(window: &mut WindowBase<R, G, Self>, keycode: KeyCode) {}
+}
+
+/// Controls of the window which exist only for bots
+pub struct BotPanels {
+	pub view: ViewMode,
+	pub brush: Brush,
+	/// Left mouse button is pressed with a painting tool
+	pub painting: bool,
+}
+
+impl Default for BotPanels {
+	fn default() -> Self {
+		BotPanels {
+			view: ViewMode::Color,
+			brush: Brush {
+				tool: Tool::Inspect,
+				size: 1,
+			},
+			painting: false,
+		}
+	}
+}
+
+/// Genomes of alive bots are written by key G to this file in the working directory, existing file is not overwritten
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_genomes_path(tick: u64) -> String {
+	format!("surviving_genomes_{}.toml", tick)
+}
+
+/// Line of the HUD with the key which exports genomes, there is no file system on the web
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORT_GENOMES_HINT: &str = "genomes: save to file (G)\n";
+#[cfg(target_arch = "wasm32")]
+const EXPORT_GENOMES_HINT: &str = "";
+
+/// Values of `tps_limit` for keys 1, 2, 3 and 4
+pub const TPS_LIMITS: [Option<u32>; 4] = [Some(1), Some(10), Some(60), None];
+
+impl<R: Rng, G: Grid<C>, C: WindowCreature> Window<R, G, C> {
+	pub fn new(constants: Constants, rng: R, cam: FloatImageCamera, world: World<G>) -> Self {
+		Window {
+			window: WindowBase::new(constants, rng, cam, world),
+			gesture_recognizer: GestureRecognizer::default(),
+		}
+	}
+}
+
+impl<R: Rng, G: Grid<C>, C: WindowCreature> WindowBase<R, G, C> {
+	fn new(constants: Constants, rng: R, cam: FloatImageCamera, world: World<G>) -> Self {
+		let font_data = include_bytes!("Anonymous Pro.ttf");
+		WindowBase {
+			image: Image::new(&Vec2i::new(1920, 1080).into()),
+			world,
+			rng,
+			cam,
+			draw: FpsWithCounter::new(20),
+			simulate: FpsWithCounter::new(20),
+			last_mouse_pos: Vec2i::default(),
+			mouse_down_pos: Vec2i::default(),
+			mouse_move: false,
+			current_cam_scale: 0.0,
+			text_cache: TextCache::new(Font::from_bytes(font_data as &[u8]).expect("Error constructing Font")),
+			performance_info: PerformanceInfo {
+				tps: 0,
+				steps_per_frame: 0,
+				fps: 0,
+			},
+			fps: FpsByLastTime::new(5.0),
+			tps: FpsByLastTime::new(5.0),
+			constants,
+			selected: BotTracker::default(),
+			paused: false,
+			single_steps: 0,
+			tps_limit: None,
+			tick_debt: 0.0,
+			tick: 0,
+			history: History::default(),
+			follow: Follow::Off,
+			panels: C::Panels::default(),
+		}
+	}
+}
+
+impl<R: Rng, G: Grid<Bot>> WindowBase<R, G, Bot> {
+	/// Applies current tool under the point on the screen
+	fn paint_at(&mut self, screen: &Vec2i) {
+		let cell = self.cell_at(screen);
+		let genome = self.selected.pos.as_ref().and_then(|pos| self.world.bots.get(pos)).cloned();
+		paint(&self.panels.brush, &self.constants, &mut self.rng, &mut self.world, &mut self.selected, genome.as_ref(), &cell);
+	}
+}
+
+impl<R, G: Grid<Bot>> WindowBase<R, G, Bot> {
+	fn tool_text(&self) -> String {
+		let mut text = format!("{:?}, brush {}", self.panels.brush.tool, self.panels.brush.size);
+		if let (Tool::Genome, None) = (self.panels.brush.tool, &self.selected.pos) {
+			text += ", select a bot first";
+		}
+		text
+	}
+}
+
+impl<R, G: Grid<C>, C: WindowCreature> WindowBase<R, G, C> {
+	/// How many ticks to make in this frame, `None` means unlimited
+	fn ticks_for_frame(&mut self) -> Option<usize> {
+		if self.paused {
+			return Some(std::mem::replace(&mut self.single_steps, 0));
+		}
+		let limit = self.tps_limit?;
+		let fps = match self.fps.fps() {
+			fps if fps > 0.0 => fps,
+			_ => 60.0,
+		};
+		self.tick_debt += f64::from(limit) / fps;
+		let ticks = self.tick_debt.floor();
+		self.tick_debt -= ticks;
+		Some(ticks as usize)
+	}
+
+	fn follow_text(&self) -> String {
+		match self.follow {
+			Follow::Off => "off".to_string(),
+			Follow::Bot => "selected".to_string(),
+			Follow::Lineage(lineage) => format!("lineage {}", lineage),
+		}
+	}
+
+	fn speed_text(&self) -> String {
+		match (self.paused, self.tps_limit) {
+			(true, _) => "paused".to_string(),
+			(false, Some(limit)) => format!("{} per second", limit),
+			(false, None) => "unlimited".to_string(),
+		}
+	}
+
+	/// Cell under the point on the screen, it may be outside of the main copy of the world
+	pub fn cell_at(&self, screen: &Vec2i) -> Vec2i {
+		let (x, y) = self.cam.to_f(screen.clone());
+		let y = y.floor() as i32;
+		let x = if let (FieldNeighborhood::Hexagonal, 1) = (&self.constants.neighborhood, y & 1) {
+			x - 0.5
+		} else {
+			x
+		};
+		Vec2i::new(x.floor() as i32, y)
+	}
+
+	pub fn screen_size(&self) -> Vec2i {
+		Vec2i::new(self.image.get_width() as i32, self.image.get_height() as i32)
+	}
+
+	/// Moves camera so the position in the world is in the center of the screen
+	pub fn center_on(&mut self, (x, y): (f32, f32)) {
+		let center = self.screen_size() / 2;
+		self.cam.offset = Vec2i::new(
+			center.x - (x * self.cam.scale) as i32,
+			center.y - (y * self.cam.scale) as i32,
+		);
+	}
+
+	/// Keeps followed bot or lineage in the center of the screen, turns following off when they disappear
+	fn follow_camera(&mut self) {
+		let center = self.cam.to_f(self.screen_size() / 2);
+		match follow_target(&self.world, self.follow, self.selected.pos.as_ref(), center) {
+			Some(target) => self.center_on(target),
+			None => self.follow = Follow::Off,
+		}
+	}
+
+	/// Off, selected creature, lineage of the selected creature if it has one
+	fn next_follow(&mut self) {
+		let selected = self.selected.pos.as_ref().and_then(|pos| self.world.bots.get(pos));
+		self.follow = match (self.follow, selected) {
+			(Follow::Off, Some(_)) => Follow::Bot,
+			(Follow::Bot, Some(creature)) => creature.lineage().map_or(Follow::Off, Follow::Lineage),
+			_ => Follow::Off,
+		};
+	}
+
+	/// Scales and moves camera so the whole world, or all bots in the infinite world, are visible
+	pub fn zoom_to_fit(&mut self) {
+		let (area_pos, area_size) = world_area(&self.world);
+		let screen = self.screen_size();
+		let scale = f32::min(screen.x as f32 / area_size.x as f32, screen.y as f32 / area_size.y as f32);
+		self.cam.scale = f32::max(scale, MIN_SCALE);
+		self.center_on((
+			area_pos.x as f32 + area_size.x as f32 / 2.0,
+			area_pos.y as f32 + area_size.y as f32 / 2.0,
+		));
+	}
+
+	/// Moves camera to the point of the minimap, returns false if the point is outside of the minimap
+	fn minimap_click(&mut self, screen: &Vec2i) -> bool {
+		let screen_size = self.screen_size();
+		let minimap = Minimap::new(&self.world, &screen_size);
+		if !minimap.is_needed(&self.cam, &screen_size) || !minimap.contains(screen) {
+			return false;
+		}
+		self.center_on(minimap.to_world(screen));
+		true
+	}
+
+	/// Selects creature under the point on the screen, or removes selection if there is no creature
+	pub fn select(&mut self, screen: &Vec2i) {
+		let cell = self.cell_at(screen);
+		self.selected.pos = if self.world.bots.can(&cell) && self.world.bots.has(&cell) {
+			Some(self.world.bots.remap(&cell))
+		} else {
+			None
+		};
+	}
+}
+
+impl<R: Rng, G: Grid<C>, C: WindowCreature> MyEvents for Window<R, G, C> {
+	fn init(&mut self) {
+		self.window.fps.clear();
+		self.window.tps.clear();
+	}
+
+	fn update(&mut self) {
+		let ticks = self.window.ticks_for_frame().unwrap_or(usize::MAX);
+		let mut counter = 0;
+		let rng = &mut self.window.rng;
+		let world = &mut self.window.world;
+		let constants = &self.window.constants;
+		let tps = &mut self.window.tps;
+		let selected = &mut self.window.selected;
+		let history = &mut self.window.history;
+		if let Some(d) = self.window.simulate.action(|clock| {
+			while clock.elapsed().fps() > 60.0 && counter < ticks {
+				let mut actions = ActionCounter::default();
+				process_world(C::rules(constants), constants, rng, world, &mut (&mut *selected, &mut actions));
+				history.push(world, actions);
+				tps.frame();
+				counter += 1;
+			}
+		}) {
+			self.window.performance_info.tps = d.fps() as usize * counter;
+			self.window.performance_info.steps_per_frame = counter;
+		}
+		self.window.tick += counter as u64;
+	}
+
+	fn draw(&mut self) {
+		self.window.follow_camera();
+		let speed = self.window.speed_text();
+		let follow = self.window.follow_text();
+		let panels_text = C::hud_text(&self.window);
+		let world = &self.window.world;
+		let image = &mut self.window.image;
+		let cam = &self.window.cam;
+		let text_cache = &mut self.window.text_cache;
+		let perf = &self.window.performance_info;
+		let fps = &self.window.fps;
+		let tps = &self.window.tps;
+		let constants = &self.window.constants;
+		let panels = &self.window.panels;
+		let bot_color = |creature: &C| creature.view_color(panels, constants);
+		let selected = &self.window.selected;
+		let history = &self.window.history;
+		let tick = self.window.tick;
+		if let Some(d) = self.window.draw.action(|_| {
+			image.clear(&(&Color::gray(0)).into());
+			let hexagonal = matches!(constants.neighborhood, FieldNeighborhood::Hexagonal);
+			draw_bots(image, cam, world, hexagonal, bot_color);
+
+			let all_resources = world.bots.iter().fold(0, |acc, x| acc + x.1.protein()) + world.resources.free_protein + world.resources.oxygen + world.resources.carbon;
+			let text = format!(
+				"\
+				simulation: {} (Space, N, 1-4)\n\
+				follow: {} (F, Z to fit)\n\
+				{}\
+				\n\
+				tick: {}\n\
+				bots: {}\n\
+				protein: {}\n\
+				oxygen: {}\n\
+				carbon: {}\n\
+				all resources: {}\n\
+				\n\
+				fps:\n\
+				potential: {}\n\
+				real:      {}\n\
+				\n\
+				simulations per second:\n\
+				potential: {}\n\
+				real:      {}\n\
+				\n\
+				simulations per frame: {}\n",
+				speed,
+				follow,
+				panels_text,
+				tick,
+				world.bots.len(),
+				world.resources.free_protein, 
+				world.resources.oxygen, 
+				world.resources.carbon,
+				all_resources,
+				perf.fps,
+				fps.fps() as i32,
+				perf.tps,
+				tps.fps() as i32,
+				perf.steps_per_frame,
+			);
+			let hud_size = draw_text_box(image, text_cache, &text, &Vec2i::new(3, 3));
+			draw_charts(image, text_cache, history, &Vec2i::new(3 + hud_size.x + 3, 3));
+
+			let screen = Vec2i::new(image.get_width() as i32, image.get_height() as i32);
+			let minimap = Minimap::new(world, &screen);
+			if minimap.is_needed(cam, &screen) {
+				minimap.draw(image, cam, world, bot_color);
+			}
+
+			let selected = selected.pos.as_ref().and_then(|pos| world.bots.get(pos).map(|creature| (pos, creature)));
+			if let Some((pos, _)) = selected {
+				draw_selection(image, cam, constants, pos);
+			}
+			C::draw_panels(image, text_cache, panels, constants, &Vec2i::new(3, 3 + hud_size.y + 3), selected);
+		}) {
+			self.window.performance_info.fps = d.fps() as usize;
+		}
+		self.window.fps.frame();
+	}
+
+	fn resize_event(&mut self, new_size: bufdraw::vec::Vec2i) {
+		let new_size: Vec2i = new_size.into();
+		self.window.image.resize_lazy(&new_size.clone().into());
+		if self.window.cam.to(Vec2i::default()) == Vec2i::default() {
+			self.window.cam.offset(&((new_size - &(self.window.constants.size() * self.window.constants.scale)) / 2));
+		self.window.fps.clear();
+		}
+	}
+
+	fn mouse_motion_event(&mut self, pos: bufdraw::vec::Vec2i, _offset: bufdraw::vec::Vec2i) {
+		let pos: Vec2i = pos.into();
+		if self.window.mouse_move {
+			self.window.cam.offset(&(pos.clone() - &self.window.last_mouse_pos));
+			if pos != self.window.last_mouse_pos {
+				self.window.follow = Follow::Off;
+			}
+		}
+		C::mouse_motion(&mut self.window, &pos);
+		self.window.last_mouse_pos = pos;
+	}
+
+	fn mouse_button_event(&mut self, button: MouseButton, state: ButtonState, pos: bufdraw::vec::Vec2i) {
+		let pos: Vec2i = pos.into();
+		self.window.last_mouse_pos = pos.clone();
+		use MouseButton::*;
+		use ButtonState::*;
+		if let Left = button {
+			let down = match state {
+				Down => true,
+				Up => false,
+				_ => return,
+			};
+			if down && self.window.minimap_click(&pos) {
+				return;
+			}
+			if C::left_button(&mut self.window, down, &pos) {
+				return;
+			}
+		}
+		match (button, state) {
+			(Left, Down) | (Right, Down) => {
+				self.window.mouse_move = true;
+				self.window.mouse_down_pos = pos;
+			},
+			(Left, Up) => {
+				self.window.mouse_move = false;
+				// Click without dragging the camera
+				let moved = pos.clone() - &self.window.mouse_down_pos;
+				if moved.x.abs() <= 2 && moved.y.abs() <= 2 {
+					self.window.select(&pos);
+				}
+			},
+			(Right, Up) => {
+				self.window.mouse_move = false;
+			},
+			_ => {},
+		}
+	}
+
+	fn mouse_wheel_event(&mut self, pos: bufdraw::vec::Vec2i, dir_vertical: MouseWheelVertical, _dir_horizontal: MouseWheelHorizontal) {
+		self.window.last_mouse_pos = pos.into();
+		match dir_vertical {
+			MouseWheelVertical::RotateUp => {
+				self.window.cam.scale_mul(&self.window.last_mouse_pos, 1.2);
+			},
+			MouseWheelVertical::RotateDown => {
+				self.window.cam.scale_mul(&self.window.last_mouse_pos, 1.0 / 1.2);
+			},
+			MouseWheelVertical::Nothing => {
+
+			}
+		}
+	}
+
+	fn key_event(&mut self, keycode: KeyCode, _keymods: KeyMods, state: ButtonState) {
+		if let bufdraw::ButtonState::Down = state {
+			match keycode {
+				KeyCode::R => {
+					for _ in 0..self.window.constants.bots {
+						insert_random_bot(&self.window.constants, &mut self.window.rng, &mut self.window.world);		
+					}
+				},
+				KeyCode::C => {
+					self.window.world.bots.clear();
+					self.window.selected.pos = None;
+				},
+				KeyCode::Escape => {
+					self.window.selected.pos = None;
+				},
+				KeyCode::F => {
+					self.window.next_follow();
+				},
+				KeyCode::Z => {
+					self.window.follow = Follow::Off;
+					self.window.zoom_to_fit();
+				},
+				KeyCode::Space => {
+					self.window.paused = !self.window.paused;
+					self.window.tick_debt = 0.0;
+				},
+				KeyCode::N => {
+					self.window.paused = true;
+					self.window.single_steps += 1;
+				},
+				KeyCode::Key1 | KeyCode::Key2 | KeyCode::Key3 | KeyCode::Key4 => {
+					let index = match keycode {
+						KeyCode::Key1 => 0,
+						KeyCode::Key2 => 1,
+						KeyCode::Key3 => 2,
+						_ => 3,
+					};
+					self.window.tps_limit = TPS_LIMITS[index];
+					self.window.tick_debt = 0.0;
+				},
+				keycode => C::key_down(&mut self.window, keycode),
+			}
+		}
+	}
+
+	fn touch_event(&mut self, phase: TouchPhase, id: u64, pos: &bufdraw::vec::Vec2i) {
+		self.gesture_recognizer.process(&mut self.window, phase.into(), id, pos.x as f32, pos.y as f32);
+	}
+}
+
+pub(crate) const HUD_TEXT_SIZE: f32 = 17.0;
+pub(crate) const HUD_BORDER: i32 = 4;
+
+/// Draws text on half-transparent background, returns size of the background
+fn draw_text_box(image: &mut Image, text_cache: &mut TextCache, text: &str, pos: &Vec2i) -> Vec2i {
+	let border_vec = Vec2i::new(HUD_BORDER, HUD_BORDER);
+	let size = text_size(text_cache, text, HUD_TEXT_SIZE) + &border_vec + &border_vec;
+	draw_rect(image, pos, &size, &Color::rgba(0, 0, 0, 150));
+	draw_text(image, text_cache, text, HUD_TEXT_SIZE, &(pos.clone() + &border_vec), &Color::rgba(255, 255, 255, 255));
+	size
+}
+
+/// Draws name of the view mode and its colors, returns size of the background
+fn draw_legend(image: &mut Image, text_cache: &mut TextCache, view: ViewMode, constants: &Constants, pos: &Vec2i) -> Vec2i {
+	let legend = view.legend(constants);
+	let mut text = format!("view: {:?} (V to switch)\n", view);
+	for (_, name) in &legend {
+		// Place for the color square
+		text += &format!("   {}\n", name);
+	}
+	let size = draw_text_box(image, text_cache, &text, pos);
+
+	let line_height = (size.y - 2 * HUD_BORDER) / (legend.len() as i32 + 1);
+	let square = Vec2i::new(line_height * 2 / 3, line_height * 2 / 3);
+	for (i, (color, _)) in legend.iter().enumerate() {
+		let line = Vec2i::new(HUD_BORDER, HUD_BORDER + line_height * (i as i32 + 1) + (line_height - square.y) / 2);
+		draw_rect(image, &(pos.clone() + &line), &square, color);
+	}
+	size
+}
+
+/// Draws state of the selected bot at the right side of the screen
+fn draw_inspect_panel(image: &mut Image, text_cache: &mut TextCache, pos: &Vec2i, bot: &Bot) {
+	let (text, eip_line) = inspect_text(pos, bot);
+	let width = text_size(text_cache, &text, HUD_TEXT_SIZE).x + 2 * HUD_BORDER;
+	let panel_pos = Vec2i::new(image.get_width() as i32 - width - 3, 3);
+	let size = draw_text_box(image, text_cache, &text, &panel_pos);
+
+	let line_height = (size.y - 2 * HUD_BORDER) / text.lines().count() as i32;
+	let line = Vec2i::new(HUD_BORDER, HUD_BORDER + line_height * eip_line as i32);
+	draw_rect(image, &(panel_pos + &line), &Vec2i::new(size.x - 2 * HUD_BORDER, line_height), &Color::rgba(255, 255, 0, 60));
+}
+
+/// Draws frame around the selected cell
+fn draw_selection(image: &mut Image, cam: &FloatImageCamera, constants: &Constants, pos: &Vec2i) {
+	let shift = if let (FieldNeighborhood::Hexagonal, 1) = (&constants.neighborhood, pos.y & 1) { 0.5 } else { 0.0 };
+	let (screen_pos, cell) = cam.cell_rect((pos.x as f32 + shift, pos.y as f32));
+	let width = std::cmp::max(cell.x / 4, 1);
+	let color = Color::rgba(255, 255, 0, 255);
+	let outer = screen_pos - &Vec2i::new(width, width);
+	let outer_size = cell.clone() + &Vec2i::new(2 * width, 2 * width);
+	rect(image, &outer, &Vec2i::new(outer_size.x, width), &color);
+	rect(image, &outer, &Vec2i::new(width, outer_size.y), &color);
+	rect(image, &(outer.clone() + &Vec2i::new(0, outer_size.y - width)), &Vec2i::new(outer_size.x, width), &color);
+	rect(image, &(outer + &Vec2i::new(outer_size.x - width, 0)), &Vec2i::new(width, outer_size.y), &color);
+}
+
+/// View modes with the legend, painting tools, the inspect panel, following of lineages and export of genomes
+impl WindowCreature for Bot {
+	type Panels = BotPanels;
+
+	fn rules(constants: &Constants) -> &Self::Rules {
+		constants.rules.ruleset()
+	}
+
+	fn view_color(&self, panels: &BotPanels, constants: &Constants) -> Color {
+		panels.view.color(self, constants)
+	}
+
+	fn lineage(&self) -> Option<u32> {
+		Some(self.lineage)
+	}
+
+	fn protein(&self) -> u32 {
+		self.protein
+	}
+
+	fn hud_text<R, G: Grid<Bot>>(window: &WindowBase<R, G, Bot>) -> String {
+		format!("tool: {} (T, [, ])\n{}", window.tool_text(), EXPORT_GENOMES_HINT)
+	}
+
+	fn draw_panels(image: &mut Image, text_cache: &mut TextCache, panels: &BotPanels, constants: &Constants, pos: &Vec2i, selected: Option<(&Vec2i, &Bot)>) {
+		draw_legend(image, text_cache, panels.view, constants, pos);
+		if let Some((pos, bot)) = selected {
+			draw_inspect_panel(image, text_cache, pos, bot);
+		}
+	}
+
+	fn left_button<R: Rng, G: Grid<Bot>>(window: &mut WindowBase<R, G, Bot>, down: bool, pos: &Vec2i) -> bool {
+		if window.panels.brush.tool == Tool::Inspect {
+			return false;
+		}
+		window.panels.painting = down;
+		if down {
+			window.paint_at(pos);
+		}
+		true
+	}
+
+	fn mouse_motion<R: Rng, G: Grid<Bot>>(window: &mut WindowBase<R, G, Bot>, pos: &Vec2i) {
+		if window.panels.painting {
+			window.paint_at(pos);
+		}
+	}
+
+	fn key_down<R: Rng, G: Grid<Bot>>(window: &mut WindowBase<R, G, Bot>, keycode: KeyCode) {
+		let brush = &mut window.panels.brush;
+		match keycode {
+			KeyCode::T => {
+				brush.tool = brush.tool.next();
+				window.mouse_move = false;
+				window.panels.painting = false;
+			},
+			KeyCode::LeftBracket => {
+				brush.size = std::cmp::max(brush.size - 1, 1);
+			},
+			KeyCode::RightBracket => {
+				brush.size = std::cmp::min(brush.size + 1, 50);
+			},
+			KeyCode::V => {
+				window.panels.view = window.panels.view.next();
+			},
+			#[cfg(not(target_arch = "wasm32"))]
+			KeyCode::G => {
+				let path = export_genomes_path(window.tick);
+				let path = std::path::Path::new(&path);
+				if path.exists() {
+					eprintln!("{} already exists, genomes are not written", path.display());
+				} else {
+					match write_surviving_genomes(&window.world, path) {
+						Ok(count) => println!("{} genomes are written to {}", count, path.display()),
+						Err(message) => eprintln!("{}", message),
+					}
+				}
+			},
+			_ => {},
+		}
+	}
+}
+
+impl<R, G, C: WindowCreature> GestureEvents for WindowBase<R, G, C> {
+	fn touch_three_move(&mut self, _pos: &Point, offset: &Point) {
+		self.cam.offset(&offset.into_my());
+	}
+
+	fn touch_one_move(&mut self, _pos: &Point, offset: &Point) {
+		self.cam.offset(&offset.into_my());
+	}
+
+	fn touch_scale_start(&mut self, _pos: &Point) {
+		self.current_cam_scale = self.cam.get_scale();
+	}
+	fn touch_scale_change(&mut self, scale: f32, pos: &Point, offset: &Point) {
+		let current_scale = self.current_cam_scale * scale;
+		self.cam.offset(&offset.into_my());
+		if (MIN_SCALE..=256.0).contains(&current_scale) {
+			self.cam.scale_new(&pos.into_my(), current_scale);
+		}
+	}
+}
+
+pub fn get_constants() -> Result<Constants, ConfigError> {
+	#[cfg(target_arch = "wasm32")]
+	let default_scale = "2";
+
+	#[cfg(not(target_arch = "wasm32"))]
+	let default_scale = "1";
+
+	let mut app = clap_app!(crabots =>
+		(setting: clap::AppSettings::ColorNever)
+		(version: env!("CARGO_PKG_VERSION"))
+		(author: 
+			"Ilya Sheprut ->\n\t\
+			<optozorax@gmail.com>,\n\t\
+			<github:optozorax>,\n\t\
+			<telegram:optozorax>,\n\t\
+			<website:optozorax.github.io>.")
+		(about: "\n\
+			Симуляция жизни в виде ботов. Когда-то здесь будет полноценное объяснение.\
+		")
+
+		(@arg width: -w --width +takes_value default_value("100") "Width of world grid")
+		(@arg height: -g --height +takes_value default_value("100") "Height of world grid")
+		(@arg scale: -s --scale +takes_value default_value("3.0") "Initial scale of cam")
+		(@arg image_scale: -a --image_scale +takes_value default_value(default_scale) "All image will be scaled by this value")
+		(@arg benchmark: -k --benchmark +takes_value default_value("false") "Run benchmark")
+
+		(@arg bots: -b --bots +takes_value default_value("400") "Initial count of bots")
+		(@arg protein: -p --protein +takes_value default_value("3000") "Initial count of free protein")
+		(@arg oxygen: -o --oxygen +takes_value default_value("1000") "Initial count of oxygen")
+		(@arg carbon: -c --carbon +takes_value default_value("1000") "Initial count of carbon")
+
+		(@arg die: -d --die +takes_value default_value("320") "Bots exists <die> steps after death")
+		(@arg live: -l --live +takes_value default_value("160") "Bot can live maximum this count of steps")
+		(@arg comand: -n --comand +takes_value default_value("2") "Maximum commands per step")
+		(@arg multiply: -m --multiply +takes_value default_value("4") "With this count of protein bot can multiply")
+		(@arg seed: -e --seed +takes_value default_value("92") "Seed to random generator")
+
+		(@arg topology: -t --topology +takes_value default_value("Torus") "Topology of space")
+		(@arg container: -r --container +takes_value default_value("Vec") "Container of bots")
+		(@arg neighborhood: -i --neighborhood +takes_value default_value("Moore") "Neighborhood of cell")
+		(@arg sense: -x --sense +takes_value default_value("1") "Radius in which bot can find another bot to attack")
+		(@arg update: -u --update +takes_value default_value("Sorted") "Order of turns of bots during a tick")
+		(@arg rules: --rules +takes_value default_value("Default") "Rules of the step of bot: Default, Ageless or Peaceful")
+		(@arg genomes: --genomes +takes_value "Initial bots are made from genomes in this TOML file, the rest is random")
+
+		(@arg config: --config +takes_value "Read values from TOML or JSON file, arguments given here override them")
+		(@arg dump_config: --("dump-config") +takes_value "Write all resulting values to TOML or JSON file")
+	);
+	#[cfg(target_arch = "wasm32")]
+	{
+		app = app.usage("index.html?help or index.html?protein=100000&topology=Infinite&a=2");
+	}
+	let matches = app.get_matches_from_safe_borrow(bufdraw::PROGRAM_PARAMETERS.iter());
+
+	let matches = match matches {
+		Ok(m) => m,
+		Err(e) => return Err(ConfigError::Arguments(e.message)),
+	};
+
+	let config = match matches.value_of("config") {
+		Some(path) => read_config(std::path::Path::new(path))?,
+		None => std::collections::HashMap::new(),
+	};
+
+	// Arguments given in the command line override values from the config, which override default values
+	let value = |name: &str| -> Option<String> {
+		match (matches.occurrences_of(name), config.get(name)) {
+			(0, Some(value)) => Some(value.clone()),
+			_ => matches.value_of(name).map(|value| value.to_string()),
+		}
+	};
+
+	macro_rules! arg_parse {
+		($name:literal) => {{
+			let value = value($name).ok_or_else(|| ConfigError::Arguments(format!("No default value for {}", $name)))?;
+			value.parse().map_err(stringify($name, &value))?
+		}};
+	}
+
+	macro_rules! arg_match_parse {
+		($name:literal, $type:ident) => {{
+			let value = value($name).ok_or_else(|| ConfigError::Arguments(format!("No default value for {}", $name)))?;
+			value.parse().map_err(stringify_unit($name, &value, &format!("Values can only be: {:?}", $type::iter().collect::<Vec<_>>())))?
+		}};
+	}
+	
+	let constants = Constants {
+		width: arg_parse!("width"),
+		height: arg_parse!("height"),
+		scale: arg_parse!("scale"),
+		image_scale: arg_parse!("image_scale"),
+		benchmark: arg_parse!("benchmark"),
+
+		bots: arg_parse!("bots"),
+		protein: arg_parse!("protein"),
+		oxygen: arg_parse!("oxygen"),
+		carbon: arg_parse!("carbon"),
+
+		die: arg_parse!("die"),
+		live: arg_parse!("live"),
+		comand: arg_parse!("comand"),
+		multiply: arg_parse!("multiply"),
+		seed: arg_parse!("seed"),
+
+		topology: arg_match_parse!("topology", FieldTopology),
+		container: arg_match_parse!("container", FieldContainer),
+		neighborhood: arg_match_parse!("neighborhood", FieldNeighborhood),
+		sense: arg_parse!("sense"),
+		update: arg_match_parse!("update", UpdateOrder),
+		rules: arg_match_parse!("rules", RulesetVariant),
+		genomes: value("genomes"),
+	};
+
+	constants.validate()?;
+
+	if let Some(path) = matches.value_of("dump_config") {
+		write_config(&constants, std::path::Path::new(path))?;
+	}
+
+	return Ok(constants);
+	
+	fn stringify<'a, T: std::fmt::Display>(param: &'a str, value: &'a str) -> impl Fn(T) -> ConfigError + 'a { 
+		move |t: T| {
+			ConfigError::Parse { key: param.to_string(), value: value.to_string(), message: t.to_string() }
+		}
+	}
+
+	fn stringify_unit<'a>(param: &'a str, value: &'a str, error: &'a str) -> impl Fn(()) -> ConfigError + 'a { 
+		move |_| {
+			ConfigError::Parse { key: param.to_string(), value: value.to_string(), message: error.to_string() }
+		}
+	}
+}
+
+/// Opens the window with the world of creatures `C`
+pub fn main3<C: WindowCreature, G: 'static + Grid<C>>(constants: Constants, rng: Pcg32, world: World<G>) {
+	let camera = FloatImageCamera {
+		offset: Vec2i::default(),
+		scale: constants.scale,
+	};
+	start(RescaledWindow { scale: constants.image_scale as i32, external: Window::<_, _, C>::new(constants, rng, camera, world) });
+}
+
+/// Opens the window with bots, initial bots are made from `genomes` and the rest is random
+struct StartWindow {
+	genomes: Vec<GenomeEntry>,
+}
+
+impl GridVisitor for StartWindow {
+	type Output = ();
+
+	fn visit<G: 'static + Grid<Bot>>(self, constants: Constants, grid: G) {
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let world = init_world_from_genomes(&constants, &mut rng, grid, &self.genomes);
+		main3(constants, rng, world);
+	}
+}
+
+/// Opens the window with random creatures `C`, they are made like random bots without genomes
+pub struct StartCreatureWindow<C>(std::marker::PhantomData<C>);
+
+impl<C> Default for StartCreatureWindow<C> {
+	fn default() -> Self {
+		StartCreatureWindow(std::marker::PhantomData)
+	}
+}
+
+impl<C: WindowCreature> GridVisitor<C> for StartCreatureWindow<C> {
+	type Output = ();
+
+	fn visit<G: 'static + Grid<C>>(self, constants: Constants, grid: G) {
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let world = init_world(&constants, &mut rng, grid);
+		main3(constants, rng, world);
+	}
+}
+
+pub fn main2() -> Result<(), String> {
+	let constants = get_constants()?;
+
+	if constants.benchmark {
+		let report = run_benchmark()?;
+		// There is no stdout on the web, so the report is shown in the window
+		#[cfg(target_arch = "wasm32")]
+		return Err(report);
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			println!("{}", report);
+			return Ok(());
+		}
+	}
+
+	let genomes = match &constants.genomes {
+		Some(path) => read_genomes(std::path::Path::new(path), &constants)?,
+		None => Vec::new(),
+	};
+	visit_grid(constants, StartWindow { genomes })?;
+
+	Ok(())
+}
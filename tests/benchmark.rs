@@ -0,0 +1,16 @@
+use crabots::*;
+
+#[test]
+fn unusable_sizes_are_reported() {
+	assert!(benchmark_cases(&[64], &[0.05]).unwrap().len() > 1);
+	assert!(benchmark_cases(&[64, 0], &[0.05]).is_err());
+	assert!(benchmark_cases(&[-64], &[0.05]).is_err());
+	assert!(benchmark_cases(&[40_000], &[0.05]).is_err());
+	assert!(benchmark_cases(&[64], &[-0.5]).is_err());
+}
+
+#[test]
+fn invalid_case_is_an_error() {
+	let case = BenchmarkCase { container: FieldContainer::Vec, topology: FieldTopology::Infinite, size: 16, density: 0.1 };
+	assert!(run_benchmark_case(&case, 1).is_err());
+}
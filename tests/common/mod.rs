@@ -0,0 +1,31 @@
+use crabots::*;
+
+/// Small valid world, tests override only the fields they need
+pub fn constants() -> Constants {
+	Constants {
+		width: 50,
+		height: 50,
+		scale: 1.0,
+		image_scale: 1,
+		benchmark: false,
+
+		bots: 200,
+		protein: 10000,
+		oxygen: 3000,
+		carbon: 3000,
+
+		die: 20,
+		live: 40,
+		comand: 2,
+		multiply: 4,
+		seed: 92,
+
+		topology: FieldTopology::Torus,
+		container: FieldContainer::Vec,
+		neighborhood: FieldNeighborhood::Moore,
+		sense: 1,
+		update: UpdateOrder::Sorted,
+		rules: RulesetVariant::Default,
+		genomes: None,
+	}
+}
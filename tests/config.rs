@@ -0,0 +1,130 @@
+mod common;
+
+use crabots::*;
+
+fn constants() -> Constants {
+	Constants {
+		width: 120,
+		height: 80,
+		scale: 2.5,
+
+		bots: 300,
+		protein: 5000,
+		oxygen: 1000,
+		carbon: 1000,
+
+		die: 320,
+		live: 160,
+		seed: 7,
+
+		topology: FieldTopology::KleinBottle,
+		container: FieldContainer::Chunked,
+		neighborhood: FieldNeighborhood::Hexagonal,
+		sense: 3,
+		genomes: Some("plants.toml".to_string()),
+		..common::constants()
+	}
+}
+
+/// Passes validation, tests change one or two values in it
+fn valid_constants() -> Constants {
+	Constants {
+		height: 81,
+		topology: FieldTopology::Mobius,
+		..constants()
+	}
+}
+
+#[test]
+fn dumped_config_is_read_back() {
+	for format in &[ConfigFormat::Toml, ConfigFormat::Json] {
+		let text = config_to_string(&constants(), *format).unwrap();
+		let values = parse_config(&text, *format).unwrap();
+
+		let mut keys: Vec<&str> = values.keys().map(|key| key.as_str()).collect();
+		let mut expected = CONFIG_KEYS.to_vec();
+		keys.sort();
+		expected.sort();
+		assert_eq!(keys, expected, "{:?}", format);
+
+		assert_eq!(values["width"], "120");
+		assert_eq!(values["scale"].parse::<f32>(), Ok(2.5));
+		assert_eq!(values["benchmark"], "false");
+		assert_eq!(values["topology"], "KleinBottle");
+		assert_eq!(values["container"], "Chunked");
+		assert_eq!(values["neighborhood"], "Hexagonal");
+	}
+}
+
+#[test]
+fn format_is_chosen_by_extension() {
+	use std::path::Path;
+	assert!(matches!(ConfigFormat::from_path(Path::new("run.toml")), Ok(ConfigFormat::Toml)));
+	assert!(matches!(ConfigFormat::from_path(Path::new("run.json")), Ok(ConfigFormat::Json)));
+	assert!(matches!(ConfigFormat::from_path(Path::new("run.yaml")), Err(ConfigError::UnknownFormat { .. })));
+	assert!(matches!(read_config(Path::new("run")), Err(ConfigError::UnknownFormat { .. })));
+}
+
+#[test]
+fn nested_values_are_rejected() {
+	assert!(parse_config("width = 10\n[bots]\ncount = 3\n", ConfigFormat::Toml).is_err());
+	assert!(parse_config(r#"{"width": [10]}"#, ConfigFormat::Json).is_err());
+}
+
+#[test]
+fn valid_constants_pass() {
+	assert_eq!(valid_constants().validate(), Ok(()));
+}
+
+#[test]
+fn zero_values_are_invalid() {
+	let mut zero_width = valid_constants();
+	zero_width.width = 0;
+	let mut zero_comand = valid_constants();
+	zero_comand.comand = 0;
+	let mut zero_multiply = valid_constants();
+	zero_multiply.multiply = 0;
+
+	for (constants, key) in [(zero_width, "width"), (zero_comand, "comand"), (zero_multiply, "multiply")].iter() {
+		match constants.validate() {
+			Err(ConfigError::Invalid { key: error_key, .. }) => assert_eq!(error_key, *key),
+			other => panic!("{}: {:?}", key, other),
+		}
+	}
+}
+
+#[test]
+fn sense_is_limited_by_world_size() {
+	let mut constants = valid_constants();
+	constants.sense = 120;
+	assert_eq!(constants.validate(), Ok(()));
+	for sense in [121, 100_000, u32::MAX].iter() {
+		constants.sense = *sense;
+		assert!(matches!(constants.validate(), Err(ConfigError::Invalid { key: "sense", .. })), "{}", sense);
+	}
+}
+
+#[test]
+fn incompatible_values_are_reported() {
+	let mut vec_infinite = valid_constants();
+	vec_infinite.container = FieldContainer::Vec;
+	vec_infinite.topology = FieldTopology::Infinite;
+	assert!(matches!(vec_infinite.validate(), Err(ConfigError::Incompatible { .. })));
+
+	let mut hexagonal_klein_bottle = valid_constants();
+	hexagonal_klein_bottle.topology = FieldTopology::KleinBottle;
+	assert!(matches!(hexagonal_klein_bottle.validate(), Err(ConfigError::Incompatible { .. })));
+
+	let mut hexagonal_odd_torus = valid_constants();
+	hexagonal_odd_torus.topology = FieldTopology::Torus;
+	assert!(matches!(hexagonal_odd_torus.validate(), Err(ConfigError::Incompatible { .. })));
+	hexagonal_odd_torus.height = 80;
+	assert_eq!(hexagonal_odd_torus.validate(), Ok(()));
+
+	let mut too_many_resources = valid_constants();
+	too_many_resources.oxygen = u32::MAX / 2;
+	too_many_resources.carbon = u32::MAX / 2;
+	assert!(matches!(too_many_resources.validate(), Err(ConfigError::Incompatible { .. })));
+	too_many_resources.protein = 1;
+	assert_eq!(too_many_resources.validate(), Ok(()));
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use crabots::*;
+
+/// Takes one protein per step and spreads when it has enough
+#[derive(Clone)]
+struct Plant {
+	protein: u32,
+}
+
+impl Gene for Plant {
+	fn make_random<R: Rng + ?Sized>(_rng: &mut R) -> Self {
+		Plant { protein: 0 }
+	}
+
+	fn mutate<R: Rng + ?Sized>(&mut self, _rng: &mut R) {}
+}
+
+impl Creature for Plant {
+	type Rules = ();
+
+	fn init(&mut self, _constants: &Constants, _lineage: u32) {}
+
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(_rules: &(), constants: &Constants, rng: &mut R, world: &mut World<G>, observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)> {
+		let mut plant = world.bots.get_owned(&pos)?;
+		if world.resources.free_protein.can_stole() {
+			plant.protein.stole(&mut world.resources.free_protein);
+		}
+		if plant.protein >= 2 {
+			let void_around = world.bots.void_around(&pos, constants.neighborhood.offsets(&pos));
+			if let Some(child_pos) = void_around.choose(rng) {
+				let child = Plant { protein: plant.protein / 2 };
+				plant.protein -= child.protein;
+				observer.birth(&pos, &plant, child_pos, &child);
+				world.bots.set_unchecked(child_pos, child);
+			}
+		}
+		Some((pos, plant))
+	}
+
+	fn decompose(&mut self, resources: &mut Resources) {
+		resources.free_protein.stole_full(&mut self.protein);
+	}
+
+	fn color(&self) -> Color {
+		Color::rgba(0, 200, 0, 255)
+	}
+}
+
+#[derive(Default)]
+struct Births(usize);
+
+impl Observer<Plant> for Births {
+	fn birth(&mut self, _parent_pos: &Vec2i, _parent: &Plant, _child_pos: &Vec2i, _child: &Plant) {
+		self.0 += 1;
+	}
+}
+
+fn constants() -> Constants {
+	Constants {
+		width: 30,
+		height: 30,
+		bots: 5,
+		protein: 300,
+		oxygen: 0,
+		carbon: 0,
+		container: FieldContainer::HashMap,
+		neighborhood: FieldNeighborhood::VonNeumann,
+		..common::constants()
+	}
+}
+
+#[test]
+fn world_hosts_other_creatures() {
+	let constants = constants();
+	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+	let mut world = init_world(&constants, &mut rng, HashMapGrid::<Plant, TorusSpace>::new(&constants.size()));
+	let initial = world.bots.len();
+
+	let mut births = Births::default();
+	for _ in 0..50 {
+		process_world(&(), &constants, &mut rng, &mut world, &mut births);
+	}
+
+	assert!(births.0 > 0);
+	assert_eq!(world.bots.len(), initial + births.0);
+	let protein: u32 = world.bots.iter().map(|(_, plant)| plant.protein).sum();
+	assert_eq!(protein + world.resources.free_protein, constants.protein);
+}
+
+#[cfg(feature = "gui")]
+impl WindowCreature for Plant {
+	type Panels = ();
+
+	fn rules(_constants: &Constants) -> &() {
+		&()
+	}
+}
+
+/// Makes one frame of the window with random plants, returns the image, its width and positions of plants
+#[cfg(feature = "gui")]
+struct Frame {
+	cam: FloatImageCamera,
+}
+
+#[cfg(feature = "gui")]
+impl GridVisitor<Plant> for Frame {
+	type Output = (Vec<u8>, usize, Vec<Vec2i>);
+
+	fn visit<G: 'static + Grid<Plant>>(self, constants: Constants, grid: G) -> Self::Output {
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let world = init_world(&constants, &mut rng, grid);
+		let mut window = Window::<_, _, Plant>::new(constants, rng, self.cam, world);
+		window.update();
+		window.draw();
+		let plants = window.window.world.bots.iter().map(|(pos, _)| pos).collect();
+		let image = &window.window.image;
+		(image.get_rgba8_buffer().to_vec(), image.get_width(), plants)
+	}
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn window_draws_other_creatures() {
+	let constants = constants();
+	let size = constants.size();
+	// Right bottom part of the screen, away from the HUD and the charts
+	let cam = FloatImageCamera { offset: Vec2i::new(1400, 700), scale: 10.0 };
+	let (image, width, plants) = visit_grid(constants, Frame { cam: cam.clone() }).unwrap();
+	assert!(!plants.is_empty());
+
+	let plant = Plant { protein: 0 }.color();
+	for y in 0..size.y {
+		for x in 0..size.x {
+			let pos = Vec2i::new(x, y);
+			let center = cam.from(pos.clone()) + &Vec2i::new(5, 5);
+			let at = (center.y as usize * width + center.x as usize) * 4;
+			let expected = if plants.contains(&pos) { [plant.r, plant.g, plant.b, 255] } else { [0, 0, 0, 255] };
+			assert_eq!(image[at..at + 4], expected, "{:?}", pos);
+		}
+	}
+}
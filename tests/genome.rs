@@ -0,0 +1,177 @@
+mod common;
+
+use crabots::*;
+use rand::SeedableRng;
+
+#[test]
+fn printed_program_parses_back() {
+	let mut rng = rand_pcg::Pcg32::seed_from_u64(17);
+	for _ in 0..100 {
+		let program = Program::make_random(&mut rng);
+		let text = program_to_string(&program);
+		assert_eq!(parse_program(&text), Ok(program), "{}", text);
+	}
+}
+
+#[test]
+fn comments_and_blank_lines_are_ignored() {
+	let text = "
+		# Plant which never moves
+		0: Photosynthesis ok->0 fail->1
+
+		1: Multiply ok->0 fail->2 # try to split
+		2: Food ok->0 fail->3
+		3: Attack ok->0 fail->4
+		4: Move ok->0 fail->0
+	";
+	let program = parse_program(text).unwrap();
+	assert_eq!(program[1].comand, Comands::Multiply);
+	assert_eq!(program[1].goto_fail.get(), 2);
+	assert_eq!(parse_program(&program_to_string(&program)), Ok(program));
+}
+
+#[test]
+fn errors_point_to_line() {
+	let out_of_bounds = "0: Photosynthesis ok->0 fail->1\n1: Multiply ok->0 fail->5\n";
+	assert_eq!(parse_program(out_of_bounds), Err(GenomeError::OutOfBounds { line: 2, pos: 5 }));
+
+	let unknown = "0: Photosynthesis ok->0 fail->1\n\n1: Sleep ok->0 fail->1\n";
+	assert!(matches!(parse_program(unknown), Err(GenomeError::Syntax { line: 3, .. })));
+
+	let wrong_index = "0: Photosynthesis ok->0 fail->1\n2: Move ok->0 fail->1\n";
+	assert!(matches!(parse_program(wrong_index), Err(GenomeError::Syntax { line: 2, .. })));
+
+	let short = "0: Photosynthesis ok->0 fail->0\n";
+	assert_eq!(parse_program(short), Err(GenomeError::Length { line: 1, count: 1 }));
+}
+
+const LIBRARY: &str = r#"
+[[genome]]
+count = 30
+color = [0, 200, 0]
+region = { x = 5, y = 10, width = 10, height = 8 }
+program = """
+0: Photosynthesis ok->0 fail->1
+1: Multiply ok->0 fail->2
+2: Food ok->0 fail->3
+3: Attack ok->0 fail->4
+4: Move ok->0 fail->0
+"""
+
+[[genome]]
+count = 20
+program = """
+0: Attack ok->1 fail->4
+1: Move ok->0 fail->2
+2: Food ok->0 fail->3
+3: Multiply ok->0 fail->4
+4: Photosynthesis ok->0 fail->0
+"""
+"#;
+
+fn constants() -> Constants {
+	Constants {
+		width: 40,
+		height: 40,
+		bots: 100,
+		..common::constants()
+	}
+}
+
+#[test]
+fn population_is_seeded_from_genomes() {
+	let genomes = parse_genomes(LIBRARY, &constants()).unwrap();
+	assert_eq!(genomes.len(), 2);
+	assert_eq!(genomes[0].color, Some(Color::rgba(0, 200, 0, 255)));
+	assert_eq!(genomes[1].region, None);
+
+	let constants = constants();
+	for seed in 0..20 {
+		let mut rng = rand_pcg::Pcg32::seed_from_u64(seed);
+		let grid = VecGrid::<Bot, TorusSpace>::new(&constants.size());
+		let world = init_world_from_genomes(&constants, &mut rng, grid, &genomes);
+
+		// Genomes get lineages in order, random bots get the next ones
+		let plants: Vec<(Vec2i, &Bot)> = world.bots.iter().filter(|(_, bot)| bot.lineage == 0).collect();
+		assert_eq!(plants.len(), 30, "seed {}", seed);
+		for (pos, bot) in plants {
+			assert!((5..15).contains(&pos.x) && (10..18).contains(&pos.y), "seed {}: {:?}", seed, pos);
+			assert_eq!(bot.program, genomes[0].program);
+			assert_eq!(bot.color, Color::rgba(0, 200, 0, 255));
+		}
+		let hunters: Vec<&Bot> = world.bots.iter().map(|(_, bot)| bot).filter(|bot| bot.lineage == 1).collect();
+		assert_eq!(hunters.len(), 20, "seed {}", seed);
+		assert!(hunters.iter().all(|bot| bot.program == genomes[1].program));
+		// Random bots which fall on occupied cells are dropped
+		assert!(world.bots.len() <= constants.bots, "seed {}: {}", seed, world.bots.len());
+	}
+}
+
+#[test]
+fn library_errors_name_the_genome() {
+	let text = LIBRARY.replace("4: Move ok->0 fail->0", "4: Move ok->0 fail->7");
+	let error = parse_genomes(&text, &constants()).unwrap_err();
+	// Line of the whole file, not of the program
+	assert!(error.contains("genome #1") && error.contains("line 11"), "{}", error);
+
+	let text = LIBRARY.replace("2: Food ok->0 fail->3\n3: Multiply", "2: Food ok->0 fail->3\n3: Sleep");
+	let error = parse_genomes(&text, &constants()).unwrap_err();
+	assert!(error.contains("genome #2") && error.contains("line 20"), "{}", error);
+
+	let text = LIBRARY.replace("4: Photosynthesis ok->0 fail->0\n", "");
+	let error = parse_genomes(&text, &constants()).unwrap_err();
+	assert!(error.contains("genome #2") && error.contains("line 20"), "{}", error);
+
+	let text = LIBRARY.replace("width = 10", "width = 0");
+	let error = parse_genomes(&text, &constants()).unwrap_err();
+	assert!(error.contains("genome #1") && error.contains("line 5"), "{}", error);
+}
+
+#[test]
+fn regions_are_checked_against_the_world() {
+	// World is 40×40, region doesn't fit by x
+	let text = LIBRARY.replace("x = 5", "x = 35");
+	let error = parse_genomes(&text, &constants()).unwrap_err();
+	assert!(error.contains("genome #1") && error.contains("line 5") && error.contains("inside the world"), "{}", error);
+
+	let mut rect = constants();
+	rect.topology = FieldTopology::Rect;
+	assert!(parse_genomes(&text, &rect).is_err());
+
+	// Infinite world has no edge to wrap to
+	let mut infinite = constants();
+	infinite.topology = FieldTopology::Infinite;
+	infinite.container = FieldContainer::Chunked;
+	let genomes = parse_genomes(&text, &infinite).unwrap();
+	let mut rng = rand_pcg::Pcg32::seed_from_u64(1);
+	let grid = ChunkGrid::<Bot, InfiniteSpace>::new_infinite();
+	let world = init_world_from_genomes(&infinite, &mut rng, grid, &genomes);
+	for (pos, _) in world.bots.iter().filter(|(_, bot)| bot.lineage == 0) {
+		assert!((35..45).contains(&pos.x) && (10..18).contains(&pos.y), "{:?}", pos);
+	}
+}
+
+#[test]
+fn surviving_genomes_are_read_back() {
+	let constants = constants();
+	let mut rng = rand_pcg::Pcg32::seed_from_u64(5);
+	let grid = VecGrid::<Bot, TorusSpace>::new(&constants.size());
+	let mut world = init_world_from_genomes(&constants, &mut rng, grid, &parse_genomes(LIBRARY, &constants).unwrap());
+	for _ in 0..50 {
+		process_world(DEFAULT_RULESET, &constants, &mut rng, &mut world, &mut ());
+	}
+
+	let genomes = surviving_genomes(&world);
+	let alive = world.bots.iter().filter(|(_, bot)| bot.alive).count();
+	assert!(alive > 0);
+	assert_eq!(genomes.iter().map(|genome| genome.count).sum::<usize>(), alive);
+	assert!(genomes.windows(2).all(|pair| pair[0].count >= pair[1].count));
+
+	let read = parse_genomes(&surviving_genomes_to_string(&genomes), &constants).unwrap();
+	assert_eq!(read.len(), genomes.len());
+	for (read, genome) in read.iter().zip(genomes.iter()) {
+		assert_eq!(read.program, genome.program);
+		assert_eq!(read.count, genome.count);
+		assert_eq!(read.color, Some(genome.color.clone()));
+	}
+}
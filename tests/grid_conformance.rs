@@ -0,0 +1,161 @@
+//! Contract that every `Grid` implementation and every `GridConstraints` topology must satisfy. New containers and spaces should be checked against it.
+
+use std::collections::HashMap;
+use crabots::*;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg32;
+
+/// All positions in rectangle from `min` to `max`, excluding `max`.
+fn area(min: &Vec2i, max: &Vec2i) -> Vec<Vec2i> {
+	(min.y..max.y).flat_map(|y| (min.x..max.x).map(move |x| Vec2i::new(x, y))).collect()
+}
+
+/// Checks that `remap` is idempotent and doesn't move positions out of space.
+fn check_space<C: GridConstraints>(space: &C, area: &[Vec2i]) {
+	for pos in area.iter().filter(|pos| space.can(pos)) {
+		let remapped = space.remap(pos);
+		assert!(space.can(&remapped), "remap({:?}) = {:?}, which is out of space", pos, remapped);
+		assert_eq!(space.remap(&remapped), remapped, "remap is not idempotent for {:?}", pos);
+	}
+}
+
+/// Same as `check_space`, and also checks that `remap` moves positions into `[0, size)`.
+fn check_finite_space<C: GridConstraints + CanFitInSize>(space: &C, area: &[Vec2i]) {
+	check_space(space, area);
+	assert!(space.is_finite());
+	let size = space.get_size();
+	for pos in area.iter().filter(|pos| space.can(pos)) {
+		let remapped = space.remap(pos);
+		assert!(
+			0 <= remapped.x && remapped.x < size.x && 0 <= remapped.y && remapped.y < size.y,
+			"remap({:?}) = {:?}, which is out of size {:?}", pos, remapped, size
+		);
+	}
+}
+
+/// Runs random operations on empty `grid` and compares it with simple model after each of them. `space` must be the same as the grid uses, `area` is the positions where operations happen.
+fn check_grid<G: Grid<u32>, C: GridConstraints>(mut grid: G, space: &C, area: &[Vec2i]) {
+	let area: Vec<Vec2i> = area.iter().filter(|pos| space.can(pos)).cloned().collect();
+	let mut rng = Pcg32::seed_from_u64(92);
+	let mut model: HashMap<Vec2i, u32> = HashMap::new();
+
+	check_state(&grid, space, &area, &model);
+	for step in 0..(area.len() as u32 * 2) {
+		let pos = area.choose(&mut rng).unwrap();
+		let key = space.remap(pos);
+		match rng.gen_range(0, 4) {
+			0 => {
+				let result = grid.set(pos, step);
+				if model.contains_key(&key) {
+					assert_eq!(result, Some(step), "set({:?}) replaced existing object", pos);
+				} else {
+					assert_eq!(result, None, "set({:?}) didn't place object into empty cell", pos);
+					model.insert(key.clone(), step);
+				}
+			},
+			1 => {
+				grid.set_unchecked(pos, step);
+				model.insert(key.clone(), step);
+			},
+			2 => {
+				assert_eq!(grid.get_owned(pos), model.remove(&key), "get_owned({:?})", pos);
+			},
+			3 => {
+				if let Some(obj) = grid.get_mut(pos) {
+					*obj = step;
+				}
+				if let Some(obj) = model.get_mut(&key) {
+					*obj = step;
+				}
+				assert_eq!(grid.get(pos), model.get(&key), "get_mut({:?})", pos);
+			},
+			_ => unreachable!(),
+		}
+		assert_eq!(grid.len(), model.len(), "len after operation on {:?}", pos);
+		assert_eq!(grid.has(pos), model.contains_key(&key), "has({:?})", pos);
+		if step % 256 == 0 {
+			check_state(&grid, space, &area, &model);
+		}
+	}
+	check_state(&grid, space, &area, &model);
+
+	grid.clear();
+	model.clear();
+	check_state(&grid, space, &area, &model);
+}
+
+fn check_state<G: Grid<u32>, C: GridConstraints>(grid: &G, space: &C, area: &[Vec2i], model: &HashMap<Vec2i, u32>) {
+	assert_eq!(grid.len(), model.len(), "len");
+	for pos in area {
+		let expected = model.get(&space.remap(pos));
+		assert_eq!(grid.has(pos), expected.is_some(), "has({:?})", pos);
+		assert_eq!(grid.get(pos), expected, "get({:?})", pos);
+		if grid.can(pos) {
+			assert_eq!(grid.remap(pos), space.remap(pos), "remap({:?})", pos);
+		}
+	}
+
+	let mut iterated = HashMap::new();
+	for (pos, obj) in grid.iter() {
+		assert_eq!(space.remap(&pos), pos, "iter returned position {:?}, which is not remapped", pos);
+		assert!(iterated.insert(pos.clone(), *obj).is_none(), "iter returned {:?} twice", pos);
+	}
+	assert_eq!(&iterated, model, "iter doesn't cover exactly the occupied cells");
+}
+
+fn finite_area(size: &Vec2i) -> Vec<Vec2i> {
+	area(&(Vec2i::default() - &(size.clone() * 2)), &(size.clone() * 3))
+}
+
+fn infinite_area() -> Vec<Vec2i> {
+	area(&Vec2i::new(-2 * CHUNK_SIZE, -CHUNK_SIZE - 3), &Vec2i::new(CHUNK_SIZE + 5, CHUNK_SIZE))
+}
+
+macro_rules! finite {
+	($name:ident, $grid:ident, $space:ident) => {
+		#[test]
+		fn $name() {
+			let size = Vec2i::new(7, 5);
+			let space = <$space as CanFitInSize>::new(&size);
+			let area = finite_area(&size);
+			check_finite_space(&space, &area);
+			check_grid($grid::<u32, $space>::new(&size), &space, &area);
+		}
+	};
+}
+
+macro_rules! infinite {
+	($name:ident, $grid:ident) => {
+		#[test]
+		fn $name() {
+			let area = infinite_area();
+			check_space(&InfiniteSpace, &area);
+			check_grid($grid::<u32, InfiniteSpace>::new_infinite(), &InfiniteSpace, &area);
+		}
+	};
+}
+
+finite!(vec_rect, VecGrid, RectSpace);
+finite!(vec_torus, VecGrid, TorusSpace);
+finite!(vec_vertical_cylinder, VecGrid, VerticalCylinderSpace);
+finite!(vec_horizontal_cylinder, VecGrid, HorizontalCylinderSpace);
+finite!(vec_mobius, VecGrid, MobiusSpace);
+finite!(vec_klein_bottle, VecGrid, KleinBottleSpace);
+
+finite!(hash_map_rect, HashMapGrid, RectSpace);
+finite!(hash_map_torus, HashMapGrid, TorusSpace);
+finite!(hash_map_vertical_cylinder, HashMapGrid, VerticalCylinderSpace);
+finite!(hash_map_horizontal_cylinder, HashMapGrid, HorizontalCylinderSpace);
+finite!(hash_map_mobius, HashMapGrid, MobiusSpace);
+finite!(hash_map_klein_bottle, HashMapGrid, KleinBottleSpace);
+infinite!(hash_map_infinite, HashMapGrid);
+
+finite!(chunk_rect, ChunkGrid, RectSpace);
+finite!(chunk_torus, ChunkGrid, TorusSpace);
+finite!(chunk_vertical_cylinder, ChunkGrid, VerticalCylinderSpace);
+finite!(chunk_horizontal_cylinder, ChunkGrid, HorizontalCylinderSpace);
+finite!(chunk_mobius, ChunkGrid, MobiusSpace);
+finite!(chunk_klein_bottle, ChunkGrid, KleinBottleSpace);
+infinite!(chunk_infinite, ChunkGrid);
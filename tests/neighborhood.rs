@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use crabots::*;
+
+fn sorted(mut cells: Vec<Vec2i>) -> Vec<Vec2i> {
+	cells.sort();
+	cells
+}
+
+fn neighbors(neighborhood: &FieldNeighborhood, pos: &Vec2i) -> Vec<Vec2i> {
+	sorted(neighborhood.offsets(pos).iter().map(|offset| offset.clone() + pos).collect())
+}
+
+/// Distances to all cells around `start` found by walking through neighbors
+fn walked_distances(neighborhood: &FieldNeighborhood, start: &Vec2i, max: i32) -> HashMap<Vec2i, i32> {
+	let mut distances = HashMap::new();
+	distances.insert(start.clone(), 0);
+	let mut front = vec![start.clone()];
+	for distance in 1..=max {
+		let mut next = Vec::new();
+		for pos in &front {
+			for cell in neighbors(neighborhood, pos) {
+				if !distances.contains_key(&cell) {
+					distances.insert(cell.clone(), distance);
+					next.push(cell);
+				}
+			}
+		}
+		front = next;
+	}
+	distances
+}
+
+#[test]
+fn hexagonal_offsets_depend_on_row_parity() {
+	let hexagonal = FieldNeighborhood::Hexagonal;
+	// Odd rows are shifted by half of the cell to the right
+	assert_eq!(neighbors(&hexagonal, &Vec2i::new(2, 2)), sorted(vec![
+		Vec2i::new(1, 1), Vec2i::new(2, 1),
+		Vec2i::new(1, 2), Vec2i::new(3, 2),
+		Vec2i::new(1, 3), Vec2i::new(2, 3),
+	]));
+	assert_eq!(neighbors(&hexagonal, &Vec2i::new(2, 3)), sorted(vec![
+		Vec2i::new(2, 2), Vec2i::new(3, 2),
+		Vec2i::new(1, 3), Vec2i::new(3, 3),
+		Vec2i::new(2, 4), Vec2i::new(3, 4),
+	]));
+	// Negative rows keep the parity
+	assert_eq!(neighbors(&hexagonal, &Vec2i::new(0, -1)), sorted(vec![
+		Vec2i::new(0, -2), Vec2i::new(1, -2),
+		Vec2i::new(-1, -1), Vec2i::new(1, -1),
+		Vec2i::new(0, 0), Vec2i::new(1, 0),
+	]));
+}
+
+#[test]
+fn hexagonal_neighbors_are_mutual() {
+	let hexagonal = FieldNeighborhood::Hexagonal;
+	for y in -2..2 {
+		for x in -2..2 {
+			let pos = Vec2i::new(x, y);
+			for neighbor in neighbors(&hexagonal, &pos) {
+				assert!(neighbors(&hexagonal, &neighbor).contains(&pos), "{:?} {:?}", pos, neighbor);
+			}
+		}
+	}
+}
+
+#[test]
+fn distance_is_count_of_steps_between_neighbors() {
+	for neighborhood in FieldNeighborhood::iter() {
+		// Start on even and odd rows, so paths cross rows of both parities
+		for start in &[Vec2i::new(0, 0), Vec2i::new(3, 1), Vec2i::new(-2, -3)] {
+			for (cell, walked) in walked_distances(&neighborhood, start, 5) {
+				assert_eq!(neighborhood.distance(start, &cell), walked, "{:?}: {:?} -> {:?}", neighborhood, start, cell);
+				assert_eq!(neighborhood.distance(&cell, start), walked, "{:?}: {:?} -> {:?}", neighborhood, cell, start);
+			}
+		}
+	}
+
+	let hexagonal = FieldNeighborhood::Hexagonal;
+	assert_eq!(hexagonal.distance(&Vec2i::new(0, 0), &Vec2i::new(1, 1)), 2);
+	assert_eq!(hexagonal.distance(&Vec2i::new(0, 1), &Vec2i::new(1, 2)), 1);
+	assert_eq!(hexagonal.distance(&Vec2i::new(0, 1), &Vec2i::new(0, 3)), 2);
+	assert_eq!(hexagonal.distance(&Vec2i::new(0, 0), &Vec2i::new(5, 0)), 5);
+}
+
+#[test]
+fn cells_in_radius_are_counted_by_neighborhood() {
+	let pos = Vec2i::new(4, 7);
+	for radius in 1..5 {
+		let r = radius as usize;
+		assert_eq!(FieldNeighborhood::Moore.cells_in_radius(&pos, radius).count(), (2 * r + 1) * (2 * r + 1) - 1);
+		assert_eq!(FieldNeighborhood::VonNeumann.cells_in_radius(&pos, radius).count(), 2 * r * (r + 1));
+		assert_eq!(FieldNeighborhood::Hexagonal.cells_in_radius(&pos, radius).count(), 3 * r * (r + 1));
+	}
+
+	for neighborhood in FieldNeighborhood::iter() {
+		for pos in &[Vec2i::new(0, 0), Vec2i::new(0, 1)] {
+			assert_eq!(sorted(neighborhood.cells_in_radius(pos, 1).collect()), neighbors(&neighborhood, pos), "{:?}", neighborhood);
+		}
+	}
+}
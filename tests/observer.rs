@@ -0,0 +1,106 @@
+mod common;
+
+use crabots::*;
+
+#[derive(Default)]
+struct Counter {
+	births: usize,
+	deaths: usize,
+	destructions: usize,
+	attacks: usize,
+	movements: usize,
+}
+
+impl Observer for Counter {
+	fn birth(&mut self, _parent_pos: &Vec2i, _parent: &Bot, _child_pos: &Vec2i, child: &Bot) {
+		assert!(child.alive);
+		self.births += 1;
+	}
+	fn death(&mut self, _pos: &Vec2i, bot: &Bot) {
+		assert!(!bot.alive);
+		self.deaths += 1;
+	}
+	fn destruction(&mut self, _pos: &Vec2i, _bot: &Bot) {
+		self.destructions += 1;
+	}
+	fn attack(&mut self, _attacker_pos: &Vec2i, _attacker: &Bot, _victim_pos: &Vec2i, _victim: &Bot, amount: u32) {
+		assert!(amount > 0);
+		self.attacks += 1;
+	}
+	fn movement(&mut self, from: &Vec2i, to: &Vec2i, _bot: &Bot) {
+		assert_ne!(from, to);
+		self.movements += 1;
+	}
+}
+
+#[test]
+fn events_explain_population() {
+	let constants = common::constants();
+	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+	let mut world = init_world(&constants, &mut rng, VecGrid::<Bot, TorusSpace>::new(&constants.size()));
+	let initial = world.bots.len();
+
+	let mut counter = Counter::default();
+	for _ in 0..300 {
+		process_world(DEFAULT_RULESET, &constants, &mut rng, &mut world, &mut counter);
+	}
+
+	assert!(counter.births > 0 && counter.deaths > 0 && counter.destructions > 0);
+	assert!(counter.attacks > 0 && counter.movements > 0);
+	assert_eq!(world.bots.len(), initial + counter.births - counter.destructions);
+}
+
+/// Always tries to move to the right neighbor, even if it is occupied
+#[cfg(feature = "gui")]
+#[derive(Clone)]
+struct Pusher;
+
+#[cfg(feature = "gui")]
+impl Gene for Pusher {
+	fn make_random<R: Rng + ?Sized>(_rng: &mut R) -> Self {
+		Pusher
+	}
+
+	fn mutate<R: Rng + ?Sized>(&mut self, _rng: &mut R) {}
+}
+
+#[cfg(feature = "gui")]
+impl Creature for Pusher {
+	type Rules = ();
+
+	fn init(&mut self, _constants: &Constants, _lineage: u32) {}
+
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(_rules: &(), _constants: &Constants, _rng: &mut R, world: &mut World<G>, _observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)> {
+		let pusher = world.bots.get_owned(&pos)?;
+		Some((pos + &Vec2i::new(1, 0), pusher))
+	}
+
+	fn decompose(&mut self, _resources: &mut Resources) {}
+
+	fn color(&self) -> Color {
+		Color::gray(255)
+	}
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn tracker_keeps_bot_when_another_one_is_destroyed_moving_into_it() {
+	let constants = common::constants();
+	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+	let mut world = empty_world(&constants, HashMapGrid::<Pusher, TorusSpace>::new(&constants.size()));
+	world.bots.set(&Vec2i::new(4, 5), Pusher);
+	world.bots.set(&Vec2i::new(5, 5), Pusher);
+
+	// Bot at (4, 5) makes its turn first and is destroyed, because (5, 5) is occupied. Bot at (5, 5) still makes its turn.
+	let mut selected = BotTracker { pos: Some(Vec2i::new(5, 5)) };
+	process_world(&(), &constants, &mut rng, &mut world, &mut selected);
+	assert_eq!(world.bots.len(), 1);
+	assert_eq!(selected.pos, Some(Vec2i::new(6, 5)));
+
+	process_world(&(), &constants, &mut rng, &mut world, &mut selected);
+	assert_eq!(selected.pos, Some(Vec2i::new(7, 5)));
+
+	world.bots.set(&Vec2i::new(8, 5), Pusher);
+	process_world(&(), &constants, &mut rng, &mut world, &mut selected);
+	assert_eq!(selected.pos, None);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use crabots::*;
+
+#[derive(Default)]
+struct Counter {
+	deaths: usize,
+	photosynthesis: usize,
+	attacks: usize,
+}
+
+impl Observer for Counter {
+	fn death(&mut self, _pos: &Vec2i, _bot: &Bot) {
+		self.deaths += 1;
+	}
+	fn photosynthesis(&mut self, _pos: &Vec2i, _bot: &Bot) {
+		self.photosynthesis += 1;
+	}
+	fn attack(&mut self, _attacker_pos: &Vec2i, _attacker: &Bot, _victim_pos: &Vec2i, _victim: &Bot, _amount: u32) {
+		self.attacks += 1;
+	}
+}
+
+fn run(rules: &'static dyn Ruleset) -> Counter {
+	let constants = common::constants();
+	let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+	let mut world = init_world(&constants, &mut rng, VecGrid::<Bot, TorusSpace>::new(&constants.size()));
+	let mut counter = Counter::default();
+	for _ in 0..100 {
+		process_world(rules, &constants, &mut rng, &mut world, &mut counter);
+	}
+	counter
+}
+
+/// Alive bots are renewed each step, so even food can't finish their timer
+struct Immortal;
+
+impl Ruleset for Immortal {
+	fn age(&self, constants: &Constants, bot: &mut Bot) {
+		if bot.alive {
+			bot.timer = constants.live;
+		} else {
+			DefaultRuleset.age(constants, bot);
+		}
+	}
+}
+
+struct NoPhotosynthesis;
+
+impl Ruleset for NoPhotosynthesis {
+	fn photosynthesis(&self, _resources: &mut Resources, _bot: &mut Bot) -> bool {
+		false
+	}
+}
+
+#[test]
+fn overridden_rules_are_used() {
+	let default = run(&DefaultRuleset);
+	assert!(default.deaths > 0 && default.photosynthesis > 0 && default.attacks > 0);
+
+	assert_eq!(run(&Immortal).deaths, 0);
+	assert_eq!(run(&NoPhotosynthesis).photosynthesis, 0);
+}
+
+#[test]
+fn variants_are_chosen_by_name() {
+	let default: RulesetVariant = "Default".parse().unwrap();
+	let default = run(default.ruleset());
+
+	let ageless: RulesetVariant = "Ageless".parse().unwrap();
+	assert!(run(ageless.ruleset()).deaths < default.deaths);
+
+	let peaceful: RulesetVariant = "Peaceful".parse().unwrap();
+	assert_eq!(run(peaceful.ruleset()).attacks, 0);
+}
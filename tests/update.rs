@@ -0,0 +1,252 @@
+mod common;
+
+use crabots::*;
+
+/// Moves to random neighbor cell each turn, eating the walker there, and counts its turns
+#[derive(Clone, Default)]
+struct Walker {
+	turns: u32,
+}
+
+impl Gene for Walker {
+	fn make_random<R: Rng + ?Sized>(_rng: &mut R) -> Self {
+		Walker::default()
+	}
+
+	fn mutate<R: Rng + ?Sized>(&mut self, _rng: &mut R) {}
+}
+
+impl Creature for Walker {
+	type Rules = ();
+
+	fn init(&mut self, _constants: &Constants, _lineage: u32) {}
+
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(_rules: &(), constants: &Constants, rng: &mut R, world: &mut World<G>, observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)> {
+		let mut walker = world.bots.get_owned(&pos)?;
+		walker.turns += 1;
+		let around = world.bots.available_cells(&pos, constants.neighborhood.offsets(&pos));
+		match around.choose(rng) {
+			Some(new_pos) => {
+				let new_pos = world.bots.remap(new_pos);
+				if let Some(eaten) = world.bots.get_owned(&new_pos) {
+					observer.destruction(&new_pos, &eaten);
+				}
+				Some((new_pos, walker))
+			},
+			None => Some((pos, walker)),
+		}
+	}
+
+	fn decompose(&mut self, _resources: &mut Resources) {}
+
+	fn color(&self) -> Color {
+		Color::gray(255)
+	}
+}
+
+/// Moves to the right neighbor each turn, even if it is occupied, and counts its turns
+#[derive(Clone, Default)]
+struct Pusher {
+	turns: u32,
+}
+
+impl Gene for Pusher {
+	fn make_random<R: Rng + ?Sized>(_rng: &mut R) -> Self {
+		Pusher::default()
+	}
+
+	fn mutate<R: Rng + ?Sized>(&mut self, _rng: &mut R) {}
+}
+
+impl Creature for Pusher {
+	type Rules = ();
+
+	fn init(&mut self, _constants: &Constants, _lineage: u32) {}
+
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(_rules: &(), _constants: &Constants, _rng: &mut R, world: &mut World<G>, _observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)> {
+		let mut pusher = world.bots.get_owned(&pos)?;
+		pusher.turns += 1;
+		Some((pos + &Vec2i::new(1, 0), pusher))
+	}
+
+	fn decompose(&mut self, _resources: &mut Resources) {}
+
+	fn color(&self) -> Color {
+		Color::gray(255)
+	}
+}
+
+/// Takes one unit of free protein each turn, if there is any
+#[derive(Clone, Default)]
+struct Eater {
+	eaten: u32,
+}
+
+impl Gene for Eater {
+	fn make_random<R: Rng + ?Sized>(_rng: &mut R) -> Self {
+		Eater::default()
+	}
+
+	fn mutate<R: Rng + ?Sized>(&mut self, _rng: &mut R) {}
+}
+
+impl Creature for Eater {
+	type Rules = ();
+
+	fn init(&mut self, _constants: &Constants, _lineage: u32) {}
+
+	fn step<R: Rng + ?Sized, G: Grid<Self>, O: Observer<Self> + ?Sized>(_rules: &(), _constants: &Constants, _rng: &mut R, world: &mut World<G>, _observer: &mut O, pos: Vec2i) -> Option<(Vec2i, Self)> {
+		let mut eater = world.bots.get_owned(&pos)?;
+		if world.resources.free_protein > 0 {
+			world.resources.free_protein -= 1;
+			eater.eaten += 1;
+		}
+		Some((pos, eater))
+	}
+
+	fn decompose(&mut self, _resources: &mut Resources) {}
+
+	fn color(&self) -> Color {
+		Color::gray(255)
+	}
+}
+
+#[derive(Default)]
+struct Counter {
+	births: usize,
+	destructions: usize,
+}
+
+impl Observer for Counter {
+	fn birth(&mut self, _parent_pos: &Vec2i, _parent: &Bot, _child_pos: &Vec2i, _child: &Bot) {
+		self.births += 1;
+	}
+	fn destruction(&mut self, _pos: &Vec2i, _bot: &Bot) {
+		self.destructions += 1;
+	}
+}
+
+fn constants(update: UpdateOrder) -> Constants {
+	Constants {
+		width: 30,
+		height: 30,
+		bots: 300,
+		container: FieldContainer::HashMap,
+		update,
+		..common::constants()
+	}
+}
+
+#[test]
+fn each_bot_makes_one_turn() {
+	for update in UpdateOrder::iter() {
+		let constants = constants(update.clone());
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut world = init_world(&constants, &mut rng, HashMapGrid::<Walker, TorusSpace>::new(&constants.size()));
+
+		for _ in 0..50 {
+			let positions: Vec<Vec2i> = world.bots.iter().map(|(pos, _)| pos).collect();
+			for pos in &positions {
+				world.bots.get_mut(pos).unwrap().turns = 0;
+			}
+			process_world(&(), &constants, &mut rng, &mut world, &mut ());
+			for (pos, walker) in world.bots.iter() {
+				assert_eq!(walker.turns, 1, "{:?}: walker at {:?}", update, pos);
+			}
+		}
+	}
+}
+
+#[test]
+fn moving_into_occupied_cell_gives_no_extra_turn() {
+	for update in UpdateOrder::iter() {
+		let constants = constants(update.clone());
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut world = init_world(&constants, &mut rng, HashMapGrid::<Pusher, TorusSpace>::new(&constants.size()));
+
+		for _ in 0..20 {
+			let positions: Vec<Vec2i> = world.bots.iter().map(|(pos, _)| pos).collect();
+			for pos in &positions {
+				world.bots.get_mut(pos).unwrap().turns = 0;
+			}
+			process_world(&(), &constants, &mut rng, &mut world, &mut ());
+			for (pos, pusher) in world.bots.iter() {
+				assert!(pusher.turns <= 1, "{:?}: pusher at {:?} made {} turns", update, pos, pusher.turns);
+			}
+		}
+	}
+}
+
+#[test]
+fn failed_move_doesnt_take_turn_of_occupant() {
+	for update in UpdateOrder::iter() {
+		let constants = constants(update.clone());
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut world = empty_world(&constants, HashMapGrid::<Pusher, TorusSpace>::new(&constants.size()));
+		world.bots.set(&Vec2i::new(4, 5), Pusher::default());
+		world.bots.set(&Vec2i::new(5, 5), Pusher::default());
+		process_world(&(), &constants, &mut rng, &mut world, &mut ());
+		for (pos, pusher) in world.bots.iter() {
+			assert_eq!(pusher.turns, 1, "{:?}: pusher at {:?}", update, pos);
+		}
+	}
+}
+
+#[test]
+fn simultaneous_moves_have_no_coordinate_bias() {
+	let first = Vec2i::new(0, 0);
+	let second = Vec2i::new(10, 10);
+	let eaten = |update: UpdateOrder| {
+		let constants = constants(update);
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut world = empty_world(&constants, HashMapGrid::<Eater, TorusSpace>::new(&constants.size()));
+		world.bots.set(&first, Eater::default());
+		world.bots.set(&second, Eater::default());
+		for _ in 0..200 {
+			world.resources.free_protein = 1;
+			process_world(&(), &constants, &mut rng, &mut world, &mut ());
+		}
+		(world.bots.get(&first).unwrap().eaten, world.bots.get(&second).unwrap().eaten)
+	};
+
+	assert_eq!(eaten(UpdateOrder::Sorted), (200, 0));
+	let (first, second) = eaten(UpdateOrder::SimultaneousMoves);
+	assert_eq!(first + second, 200);
+	assert!(first > 50 && second > 50, "{} {}", first, second);
+}
+
+#[test]
+fn events_explain_population_with_any_order() {
+	for update in UpdateOrder::iter() {
+		let constants = constants(update.clone());
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut world = init_world(&constants, &mut rng, HashMapGrid::<Bot, TorusSpace>::new(&constants.size()));
+		let initial = world.bots.len();
+
+		let mut counter = Counter::default();
+		for _ in 0..200 {
+			process_world(DEFAULT_RULESET, &constants, &mut rng, &mut world, &mut counter);
+		}
+
+		assert!(counter.births > 0, "{:?}", update);
+		assert_eq!(world.bots.len(), initial + counter.births - counter.destructions, "{:?}", update);
+	}
+}
+
+#[test]
+fn same_seed_gives_same_world_with_any_container_order() {
+	let run = |update: UpdateOrder| {
+		let constants = constants(update);
+		let mut rng = Pcg32::from_seed(gen_seed(constants.seed));
+		let mut world = init_world(&constants, &mut rng, HashMapGrid::<Bot, TorusSpace>::new(&constants.size()));
+		for _ in 0..50 {
+			process_world(DEFAULT_RULESET, &constants, &mut rng, &mut world, &mut ());
+		}
+		let mut bots: Vec<String> = world.bots.iter().map(|(pos, bot)| format!("{:?} {:?}", pos, bot)).collect();
+		bots.sort();
+		(bots, world.resources.free_protein, world.resources.oxygen, world.resources.carbon)
+	};
+	for update in UpdateOrder::iter() {
+		assert!(run(update.clone()) == run(update.clone()), "{:?}", update);
+	}
+}